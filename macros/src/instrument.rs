@@ -0,0 +1,161 @@
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+use crate::compile_error;
+
+struct Args {
+    tag: String,
+    prio: String,
+    args: Vec<String>,
+    ret: bool,
+    err: bool,
+}
+
+//Parses `tag = "literal", prio = PRIO, args(name, ...), ret, err` in any order.
+fn parse_args(args: TokenStream) -> Result<Args, TokenStream> {
+    let mut result = Args {
+        tag: "None".into(),
+        prio: "DEBUG".into(),
+        args: Vec::new(),
+        ret: false,
+        err: false,
+    };
+
+    let tokens: Vec<TokenTree> = args.into_iter().collect();
+    for arg in tokens.split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ',')) {
+        match arg {
+            [] => (),
+            [TokenTree::Ident(name)] if name.to_string() == "ret" => result.ret = true,
+            [TokenTree::Ident(name)] if name.to_string() == "err" => result.err = true,
+            [TokenTree::Ident(name), TokenTree::Group(names)] if name.to_string() == "args" && names.delimiter() == Delimiter::Parenthesis => {
+                for name in names.stream() {
+                    match name {
+                        TokenTree::Ident(name) => result.args.push(name.to_string()),
+                        TokenTree::Punct(punct) if punct.as_char() == ',' => (),
+                        _ => return Err(compile_error("args must be list of argument names")),
+                    }
+                }
+            },
+            [TokenTree::Ident(name), TokenTree::Punct(eq), TokenTree::Literal(tag)] if name.to_string() == "tag" && eq.as_char() == '=' => {
+                let tag = tag.to_string();
+                if !tag.starts_with('"') {
+                    return Err(compile_error("tag must be string literal"));
+                }
+                result.tag = format!("Some(::androidy_log::TagHandle::new({}))", tag);
+            },
+            [TokenTree::Ident(name), TokenTree::Punct(eq), prio @ ..] if name.to_string() == "prio" && eq.as_char() == '=' && !prio.is_empty() => {
+                result.prio = prio.iter().cloned().collect::<TokenStream>().to_string();
+            },
+            _ => return Err(compile_error("expected `tag = \"...\"`, `prio = ...`, `args(...)`, `ret` or `err`")),
+        }
+    }
+
+    Ok(result)
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ch)
+}
+
+//Returns function name and explicit return type, if any.
+fn parse_signature(item: &[TokenTree]) -> Result<(String, Option<TokenStream>), TokenStream> {
+    let fn_idx = item.iter().position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"));
+    let fn_idx = match fn_idx {
+        Some(idx) => idx,
+        None => return Err(compile_error("#[log_instrument] can only be applied to function")),
+    };
+    if item[..fn_idx].iter().any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "async")) {
+        return Err(compile_error("#[log_instrument] doesn't support async functions"));
+    }
+
+    let name = match item.get(fn_idx + 1) {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => return Err(compile_error("#[log_instrument] can only be applied to function")),
+    };
+
+    //Skip generics up to parameters
+    let mut idx = fn_idx + 2;
+    let mut depth = 0usize;
+    loop {
+        match item.get(idx) {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '-' && is_punct(item.get(idx + 1), '>') => idx += 1,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => depth += 1,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => depth = depth.saturating_sub(1),
+            Some(TokenTree::Group(group)) if depth == 0 && group.delimiter() == Delimiter::Parenthesis => break,
+            Some(_) => (),
+            None => return Err(compile_error("#[log_instrument] can only be applied to function")),
+        }
+        idx += 1;
+    }
+    idx += 1;
+
+    if !(is_punct(item.get(idx), '-') && is_punct(item.get(idx + 1), '>')) {
+        return Ok((name, None));
+    }
+
+    let end = item.len() - 1;
+    let ret = item[idx + 2..end].iter().take_while(|token| !matches!(token, TokenTree::Ident(ident) if ident.to_string() == "where"));
+    let ret: TokenStream = ret.cloned().collect();
+    //Closures cannot return `impl Trait`, so let compiler infer it.
+    if ret.clone().into_iter().any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "impl")) {
+        Ok((name, None))
+    } else {
+        Ok((name, Some(ret)))
+    }
+}
+
+fn parse(code: &str) -> TokenStream {
+    code.parse().unwrap()
+}
+
+pub fn expand(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(error) => return error,
+    };
+
+    let mut item: Vec<TokenTree> = item.into_iter().collect();
+    let (name, ret_type) = match parse_signature(&item) {
+        Ok(signature) => signature,
+        Err(error) => return error,
+    };
+
+    let body = match item.last_mut() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return compile_error("#[log_instrument] can only be applied to function with body"),
+    };
+
+    let fmt = args.args.iter().map(|arg| format!("{}={{:?}}", arg)).collect::<Vec<_>>().join(", ");
+    let mut stream = parse(&format!(
+        "let __androidy_log_instrument = ::androidy_log::__Instrument::enter({}, ::androidy_log::__priority!({}), {:?}, format_args!({:?}{}));",
+        args.tag, args.prio, name, fmt, args.args.iter().map(|arg| format!(", {}", arg)).collect::<String>()
+    ));
+
+    let mut closure = match ret_type {
+        Some(ret_type) => {
+            let mut closure = parse("|| ->");
+            closure.extend(ret_type);
+            closure
+        },
+        None => parse("||"),
+    };
+    closure.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, body.stream()))));
+
+    stream.extend(parse("#[allow(clippy::redundant_closure_call)] let __androidy_log_ret ="));
+    stream.extend(Some(TokenTree::Group(Group::new(Delimiter::Parenthesis, closure))));
+    stream.extend(parse("();"));
+    if args.err {
+        stream.extend(parse("if let ::core::result::Result::Err(error) = &__androidy_log_ret { __androidy_log_instrument.error(error); }"));
+    }
+    if args.ret {
+        stream.extend(parse("__androidy_log_instrument.exit(Some(&__androidy_log_ret as &dyn ::core::fmt::Debug));"));
+    } else {
+        stream.extend(parse("__androidy_log_instrument.exit(None);"));
+    }
+    stream.extend(parse("__androidy_log_ret"));
+
+    let mut new_body = Group::new(Delimiter::Brace, stream);
+    new_body.set_span(body.span());
+    *body = new_body;
+
+    item.into_iter().collect()
+}
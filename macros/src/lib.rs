@@ -0,0 +1,63 @@
+//! Procedural macros for `androidy-log`.
+//!
+//! Use them via `androidy-log`'s `macros` feature.
+
+extern crate proc_macro;
+
+mod instrument;
+
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("compile_error!({:?});", message).parse().unwrap()
+}
+
+//Parses `tag = "literal"` into expression of `Option<&str>`.
+fn parse_args(args: TokenStream) -> Result<String, TokenStream> {
+    let args: Vec<TokenTree> = args.into_iter().collect();
+
+    match args.as_slice() {
+        [] => Ok("None".into()),
+        [TokenTree::Ident(name), TokenTree::Punct(eq), TokenTree::Literal(tag)] if name.to_string() == "tag" && eq.as_char() == '=' => {
+            let tag = tag.to_string();
+            if tag.starts_with('"') {
+                Ok(format!("Some({})", tag))
+            } else {
+                Err(compile_error("tag must be string literal"))
+            }
+        },
+        _ => Err(compile_error("expected no arguments or `tag = \"...\"`")),
+    }
+}
+
+#[proc_macro_attribute]
+///Sets up logging at the start of entry point, refer to `androidy_log::main`.
+pub fn main(args: TokenStream, item: TokenStream) -> TokenStream {
+    let tag = match parse_args(args) {
+        Ok(tag) => tag,
+        Err(error) => return error,
+    };
+
+    let mut item: Vec<TokenTree> = item.into_iter().collect();
+    let is_fn = item.iter().any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"));
+
+    match item.last_mut() {
+        Some(TokenTree::Group(body)) if is_fn && body.delimiter() == Delimiter::Brace => {
+            let mut stream: TokenStream = format!("::androidy_log::__main_init({});", tag).parse().unwrap();
+            stream.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, body.stream()))));
+
+            let mut new_body = Group::new(Delimiter::Brace, stream);
+            new_body.set_span(body.span());
+            *body = new_body;
+        },
+        _ => return compile_error("#[main] can only be applied to function"),
+    }
+
+    item.into_iter().collect()
+}
+
+#[proc_macro_attribute]
+///Logs entry, exit and duration of function, refer to `androidy_log::log_instrument`.
+pub fn log_instrument(args: TokenStream, item: TokenStream) -> TokenStream {
+    instrument::expand(args, item)
+}
@@ -0,0 +1,140 @@
+use crate::{AsTag, LogId, LogPriority, Overflow, Writer};
+
+use core::ffi::CStr;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ABORTER: AtomicUsize = AtomicUsize::new(0);
+static LIBLOG_ABORTER: AtomicBool = AtomicBool::new(false);
+
+///Sets `aborter`, called with abort message before process aborts on fatal failure.
+///
+///Allows application specific crash handling, like flushing crash report, but process is aborted once it returns.
+///It is called by `Writer::abort`, `Writer::assert_failed` and macros relying on them.
+///
+///On Android 11+ it is also set as liblog's aborter via `__android_log_set_aborter`, so that it is called on
+///fatal failures of other code in the process, like `LOG_ALWAYS_FATAL`.
+///Returns whether liblog's aborter is set.
+pub fn set_aborter(aborter: fn(&str)) -> bool {
+    ABORTER.store(aborter as usize, Ordering::Release);
+    let is_set = crate::sys::set_aborter();
+    LIBLOG_ABORTER.store(is_set, Ordering::Release);
+    is_set
+}
+
+//Calls aborter, if any.
+pub(crate) fn call_aborter(message: &str) {
+    match ABORTER.load(Ordering::Acquire) {
+        0 => (),
+        aborter => {
+            let aborter: fn(&str) = unsafe { core::mem::transmute(aborter) };
+            aborter(message);
+        },
+    }
+}
+
+impl<T: AsTag> Writer<T> {
+    ///Writes message as its own entry with `FATAL` priority, then aborts process.
+    ///
+    ///On Android message is also set as abort message, making it visible in tombstone.
+    ///Message is truncated to fit single entry.
+    ///
+    ///With `std` feature, buffered output of `print!` and stdout, including one redirected by `redirect_stdio`, is
+    ///written into log before message, so that it is not lost on abort.
+    ///
+    ///Message is written into `LogId::CRASH` buffer.
+    pub fn abort(mut self, args: fmt::Arguments<'_>) -> ! {
+        #[cfg(feature = "std")]
+        crate::rt::drain_stdio();
+
+        self.flush();
+        self.prio = LogPriority::FATAL;
+        self.log_id = LogId::CRASH;
+        //Abort message must be complete entry, rather than its last chunk
+        self.set_overflow(Overflow::TruncateWithEllipsis);
+        let _ = self.write_fmt(args);
+
+        //Buffer always has space for terminating NUL
+        unsafe {
+            self.as_mut_ptr().add(self.len).write(0);
+            let message = CStr::from_ptr(self.buffer.as_ptr() as _);
+            crate::sys::set_abort_message(message);
+            call_aborter(message.to_str().unwrap_or_default());
+        }
+        drop(self);
+
+        crate::sys::abort()
+    }
+
+    ///Fails assertion `cond` with message, then aborts process, the same way as `LOG_ALWAYS_FATAL_IF` in C.
+    ///
+    ///On Android it is written via `__android_log_assert`, producing the same tombstone as C/C++ code.
+    ///Without message, it is `Assertion failed: <cond>`.
+    ///Message is truncated to fit single entry, and on Android it is truncated by liblog to about 1000 bytes.
+    ///
+    ///With `std` feature, buffered output of stdout is written into log before message, like in `Writer::abort`.
+    pub fn assert_failed(mut self, cond: &str, args: fmt::Arguments<'_>) -> ! {
+        const COND_MAX_LEN: usize = 127;
+
+        #[cfg(feature = "std")]
+        crate::rt::drain_stdio();
+
+        self.flush();
+        self.prio = LogPriority::FATAL;
+        self.set_overflow(Overflow::TruncateWithEllipsis);
+        let _ = match args.as_str() {
+            Some("") => write!(self, "Assertion failed: {}", cond),
+            _ => self.write_fmt(args),
+        };
+
+        let mut cond_len = core::cmp::min(cond.len(), COND_MAX_LEN);
+        while !cond.is_char_boundary(cond_len) {
+            cond_len -= 1;
+        }
+        let mut cond_buffer = [0u8; COND_MAX_LEN + 1];
+        cond_buffer[..cond_len].copy_from_slice(&cond.as_bytes()[..cond_len]);
+
+        //Buffer always has space for terminating NUL
+        unsafe {
+            self.as_mut_ptr().add(self.len).write(0);
+            let cond = CStr::from_ptr(cond_buffer.as_ptr() as _);
+            let message = CStr::from_ptr(self.buffer.as_ptr() as _);
+            //liblog calls its aborter by itself
+            if !LIBLOG_ABORTER.load(Ordering::Acquire) {
+                call_aborter(message.to_str().unwrap_or_default());
+            }
+            crate::sys::log_assert(cond, self.tag.as_tag_ptr(), message)
+        }
+    }
+}
+
+#[inline]
+///Writes message with `FATAL` priority using default tag, then aborts process.
+///
+///Refer to `Writer::abort` for details.
+pub fn abort(args: fmt::Arguments<'_>) -> ! {
+    Writer::new_default(LogPriority::FATAL).abort(args)
+}
+
+#[inline]
+///Fails assertion `cond` with message using `tag`, then aborts process.
+///
+///Refer to `Writer::assert_failed` for details.
+pub fn log_assert(cond: &str, tag: &str, args: fmt::Arguments<'_>) -> ! {
+    Writer::new(tag, LogPriority::FATAL).assert_failed(cond, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+    use std::sync::Mutex;
+
+    static MESSAGE: Mutex<String> = Mutex::new(String::new());
+
+    #[test]
+    fn should_call_aborter() {
+        assert!(!super::set_aborter(|message| *MESSAGE.lock().unwrap() = message.into()));
+        super::call_aborter("fatal");
+        assert_eq!(*MESSAGE.lock().unwrap(), "fatal");
+    }
+}
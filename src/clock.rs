@@ -0,0 +1,113 @@
+use core::time::Duration;
+
+#[cfg(unix)]
+mod sys {
+    use core::ffi::c_long;
+    use core::time::Duration;
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten", target_os = "l4re", target_os = "hurd"))]
+    const CLOCK_MONOTONIC: i32 = 1;
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos", target_os = "visionos"))]
+    const CLOCK_MONOTONIC: i32 = 6;
+    #[cfg(any(target_os = "freebsd", target_os = "dragonfly", target_os = "solaris", target_os = "illumos"))]
+    const CLOCK_MONOTONIC: i32 = 4;
+    #[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+    const CLOCK_MONOTONIC: i32 = 3;
+    //Id of monotonic clock is unknown, hence system time is used, whose id is the same on every unix
+    #[cfg(not(any(
+        target_os = "linux", target_os = "android", target_os = "emscripten", target_os = "l4re", target_os = "hurd",
+        target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos", target_os = "visionos",
+        target_os = "freebsd", target_os = "dragonfly", target_os = "solaris", target_os = "illumos",
+        target_os = "openbsd", target_os = "netbsd",
+    )))]
+    const CLOCK_MONOTONIC: i32 = CLOCK_REALTIME;
+    const CLOCK_REALTIME: i32 = 0;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    extern "C" {
+        fn clock_gettime(clock: i32, time: *mut Timespec) -> i32;
+    }
+
+    pub fn now() -> Duration {
+        let mut time = Timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        let result = unsafe {
+            clock_gettime(CLOCK_MONOTONIC, &mut time)
+        };
+        debug_assert_eq!(result, 0, "clock_gettime(CLOCK_MONOTONIC) failed");
+        //System time is not monotonic, but `duration_since` saturates, so it is still better than constant zero
+        if result != 0 {
+            unsafe {
+                clock_gettime(CLOCK_REALTIME, &mut time);
+            }
+        }
+        Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use core::time::Duration;
+    use std::sync::OnceLock;
+
+    pub fn now() -> Duration {
+        static START: OnceLock<std::time::Instant> = OnceLock::new();
+        START.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///Monotonic clock measurement, available without `std` on unix targets.
+///
+///Uses `CLOCK_MONOTONIC`, which is not affected by changes of system time.
+pub struct Instant(Duration);
+
+impl Instant {
+    #[inline]
+    ///Returns current time.
+    pub fn now() -> Self {
+        Self(sys::now())
+    }
+
+    #[inline]
+    ///Returns time elapsed since `earlier`, or zero if `earlier` is later.
+    pub fn duration_since(&self, earlier: Self) -> Duration {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    #[inline(always)]
+    //Time since unspecified point, which must be the same for all instants.
+    pub(crate) fn since_epoch(&self) -> Duration {
+        self.0
+    }
+
+    #[inline]
+    ///Returns time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Instant;
+
+    #[test]
+    fn should_measure_monotonic_time() {
+        let start = Instant::now();
+        std::thread::sleep(core::time::Duration::from_millis(2));
+        let now = Instant::now();
+
+        assert!(now > start);
+        assert!(now.duration_since(start) >= core::time::Duration::from_millis(2));
+        assert_eq!(start.duration_since(now), core::time::Duration::ZERO);
+        assert!(start.elapsed() >= now.duration_since(start));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::TagHandle;
+
+use std::sync::OnceLock;
+
+static PACKAGE_TAG: OnceLock<Option<TagHandle>> = OnceLock::new();
+
+///Returns tag derived from application's package name.
+///
+///Package name is retrieved once via `ndk-context`'s cached `JavaVM` and `Context`, which must be
+///initialized beforehand, for example by `android-activity`.
+///
+///Returns `None` if package name is not available, which is always the case on non-Android targets.
+pub fn package_tag() -> Option<&'static TagHandle> {
+    PACKAGE_TAG.get_or_init(|| package_name().map(|name| TagHandle::new(&name))).as_ref()
+}
+
+#[cfg(target_os = "android")]
+fn package_name() -> Option<std::string::String> {
+    //ndk-context panics when it is not initialized.
+    let ctx = std::panic::catch_unwind(ndk_context::android_context).ok()?;
+    let vm = unsafe {
+        jni::JavaVM::from_raw(ctx.vm().cast()).ok()?
+    };
+    let context = unsafe {
+        jni::objects::JObject::from_raw(ctx.context().cast())
+    };
+    let mut env = vm.attach_current_thread().ok()?;
+
+    let name = match env.call_method(&context, "getPackageName", "()Ljava/lang/String;", &[]).and_then(|name| name.l()) {
+        Ok(name) => jni::objects::JString::from(name),
+        Err(_) => {
+            let _ = env.exception_clear();
+            return None;
+        }
+    };
+    let name = env.get_string(&name).ok()?.into();
+    Some(name)
+}
+
+#[cfg(not(target_os = "android"))]
+#[inline(always)]
+fn package_name() -> Option<std::string::String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::package_tag;
+
+    #[test]
+    fn should_not_have_package_tag_on_host() {
+        assert!(package_tag().is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use core::fmt;
+use std::error::Error;
+
+#[derive(Clone, Copy)]
+///Error written with its `source()` chain, as indented `Caused by:` lines.
+///
+///```rust
+///use androidy_log::ErrorChain;
+///
+///let error = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+///assert_eq!(ErrorChain(&error).to_string(), "disk full");
+///```
+pub struct ErrorChain<'a>(pub &'a (dyn Error + 'a));
+
+impl fmt::Display for ErrorChain<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, fmt)?;
+
+        let mut source = self.0.source();
+        while let Some(error) = source {
+            write!(fmt, "\n  Caused by: {}", error)?;
+            source = error.source();
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ErrorChain<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+#[macro_export]
+///Writes error and each of its sources with ERROR priority, if it is enabled.
+///
+///Requires `std` feature.
+///
+///```rust,no_run
+///fn read_config() -> std::io::Result<String> {
+///    std::fs::read_to_string("/data/local/tmp/config")
+///}
+///
+///if let Err(error) = read_config() {
+///    androidy_log::log_error_chain!(tag: "MyApp", &error);
+///}
+///```
+macro_rules! log_error_chain {
+    (tag: $tag:expr, $error:expr) => {
+        $crate::log!($crate::LogPriority::ERROR, tag: $tag, "{}", $crate::ErrorChain($error))
+    };
+    ($error:expr) => {
+        $crate::log!($crate::LogPriority::ERROR, "{}", $crate::ErrorChain($error))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorChain;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    use core::fmt;
+    use std::error::Error;
+
+    #[derive(Debug)]
+    struct Wrapped(&'static str, Option<Box<dyn Error>>);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str(self.0)
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_deref()
+        }
+    }
+
+    #[test]
+    fn should_log_error_chain() {
+        let root = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "permission denied");
+        let error = Wrapped("failed to load config", Some(Box::new(Wrapped("failed to open file", Some(Box::new(root))))));
+
+        crate::log_error_chain!(tag: "Config", &error);
+        crate::log_error_chain!(&Wrapped("no source", None));
+
+        let logs = capture::take();
+        assert_eq!(logs[0], (LogPriority::ERROR as i32, "Config".into(), "failed to load config\n  Caused by: failed to open file\n  Caused by: permission denied".into()));
+        assert_eq!(logs[1].2, "no source");
+        assert_eq!(format!("{:?}", ErrorChain(&error)), logs[0].2);
+    }
+}
@@ -0,0 +1,576 @@
+//! Android event log utilities.
+//!
+//! Binary events are written into `LogId::EVENTS` buffer by numeric tag, like `EventLog.writeEvent` in Java.
+//! Tags are described by `/system/etc/event-log-tags` and can be viewed via `adb logcat -b events`.
+//!
+//! Writing functions return result of liblog, which is negative `errno` on failure.
+//! On other platforms binary events are discarded.
+//!
+//!```rust,no_run
+//!use androidy_log::events;
+//!
+//!events::write_int(42, 1);
+//!events::write_str(42, "started");
+//!```
+
+use crate::BUFFER_CAPACITY;
+
+use core::fmt;
+
+///Type of binary event's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EventType {
+    ///32-bit integer.
+    INT = 0,
+    ///64-bit integer.
+    LONG = 1,
+    ///String prefixed by its 32-bit length.
+    STRING = 2,
+    ///List prefixed by number of its elements.
+    LIST = 3,
+    ///32-bit float.
+    FLOAT = 4,
+}
+
+#[inline]
+///Writes event with already encoded `payload`, that starts with type of the value.
+///
+///Wraps `__android_log_bwrite`.
+pub fn write(tag: u32, payload: &[u8]) -> i32 {
+    crate::sys::event_write(tag as i32, payload)
+}
+
+#[inline]
+///Writes event with `payload` encoded as value of type `kind`.
+///
+///Wraps `__android_log_btwrite`.
+pub fn write_typed(tag: u32, kind: EventType, payload: &[u8]) -> i32 {
+    crate::sys::event_write_typed(tag as i32, kind as u8, payload)
+}
+
+#[inline]
+///Writes event with single integer.
+pub fn write_int(tag: u32, value: i32) -> i32 {
+    write_typed(tag, EventType::INT, &value.to_le_bytes())
+}
+
+#[inline]
+///Writes event with single long integer.
+pub fn write_long(tag: u32, value: i64) -> i32 {
+    write_typed(tag, EventType::LONG, &value.to_le_bytes())
+}
+
+#[inline]
+///Writes event with single float.
+pub fn write_float(tag: u32, value: f32) -> i32 {
+    write_typed(tag, EventType::FLOAT, &value.to_le_bytes())
+}
+
+///Writes event with single string, truncated to fit event buffer without splitting UTF-8 characters.
+///
+///Equivalent of `__android_log_bswrite`, but `value` doesn't need to be null terminated.
+pub fn write_str(tag: u32, value: &str) -> i32 {
+    const HEADER_LEN: usize = 5;
+
+    let mut len = core::cmp::min(value.len(), BUFFER_CAPACITY - HEADER_LEN);
+    while !value.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    let mut payload = [0u8; BUFFER_CAPACITY];
+    payload[0] = EventType::STRING as u8;
+    payload[1..HEADER_LEN].copy_from_slice(&(len as u32).to_le_bytes());
+    payload[HEADER_LEN..HEADER_LEN + len].copy_from_slice(&value.as_bytes()[..len]);
+    write(tag, &payload[..HEADER_LEN + len])
+}
+
+///Failure to write into security buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityLogError {
+    ///Security buffer is not supported by this platform.
+    Unavailable,
+    ///Caller is not allowed to write into security buffer.
+    ///
+    ///Security logging must be enabled by device owner, while writer must be system component.
+    PermissionDenied,
+    ///liblog failed with specified `errno`.
+    Failed(i32),
+}
+
+impl SecurityLogError {
+    const EPERM: i32 = 1;
+    const EACCES: i32 = 13;
+
+    //Maps result of liblog.
+    fn from_result(result: i32) -> Result<(), Self> {
+        match -result {
+            Self::EPERM | Self::EACCES => Err(SecurityLogError::PermissionDenied),
+            errno if errno > 0 => Err(SecurityLogError::Failed(errno)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for SecurityLogError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityLogError::Unavailable => fmt.write_str("security log is not available"),
+            SecurityLogError::PermissionDenied => fmt.write_str("not permitted to write security log"),
+            SecurityLogError::Failed(errno) => write!(fmt, "failed to write security log, errno={}", errno),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SecurityLogError {}
+
+#[inline]
+///Writes event with already encoded `payload` into `LogId::SECURITY` buffer.
+///
+///Wraps `__android_log_security_bwrite`, which is only available to system components.
+///Intended for device owner's security logging, that is collected by `DevicePolicyManager`.
+pub fn write_security(tag: u32, payload: &[u8]) -> Result<(), SecurityLogError> {
+    match crate::sys::security_event_write(tag as i32, payload) {
+        Some(result) => SecurityLogError::from_result(result),
+        None => Err(SecurityLogError::Unavailable),
+    }
+}
+
+///Value of binary event's payload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventValue<'a> {
+    ///32-bit integer.
+    Int(i32),
+    ///64-bit integer.
+    Long(i64),
+    ///32-bit float.
+    Float(f32),
+    ///String, truncated to fit event buffer.
+    String(&'a str),
+    ///List of values, up to 255 elements.
+    List(&'a [EventValue<'a>]),
+}
+
+impl From<i32> for EventValue<'_> {
+    #[inline(always)]
+    fn from(value: i32) -> Self {
+        EventValue::Int(value)
+    }
+}
+
+impl From<i64> for EventValue<'_> {
+    #[inline(always)]
+    fn from(value: i64) -> Self {
+        EventValue::Long(value)
+    }
+}
+
+impl From<f32> for EventValue<'_> {
+    #[inline(always)]
+    fn from(value: f32) -> Self {
+        EventValue::Float(value)
+    }
+}
+
+impl<'a> From<&'a str> for EventValue<'a> {
+    #[inline(always)]
+    fn from(value: &'a str) -> Self {
+        EventValue::String(value)
+    }
+}
+
+impl<'a> From<&'a [EventValue<'a>]> for EventValue<'a> {
+    #[inline(always)]
+    fn from(value: &'a [EventValue<'a>]) -> Self {
+        EventValue::List(value)
+    }
+}
+
+impl EventValue<'_> {
+    #[inline]
+    ///Writes event with this value as payload.
+    pub fn write(&self, tag: u32) -> i32 {
+        let mut builder = EventBuilder::new();
+        builder.value(self);
+        builder.write(tag)
+    }
+}
+
+const LIST_MAX_LEN: u8 = u8::MAX;
+
+///Builder of binary event's payload, encoded in event log format.
+///
+///Single value is written as it is, while multiple values are written as list, like `EventLog.writeEvent(tag, Object...)` does.
+///Payload is limited by 4000 bytes, values that do not fit are skipped and reported via `is_truncated`.
+///
+///```rust,no_run
+///use androidy_log::events::EventBuilder;
+///
+///let mut event = EventBuilder::new();
+///event.int(1).string("connected").list(|list| {
+///    list.long(2).float(0.5);
+///});
+///event.write(42);
+///```
+pub struct EventBuilder {
+    //Starts with header of top level list
+    buffer: [u8; BUFFER_CAPACITY],
+    len: usize,
+    //Position of element count of current list
+    count_pos: usize,
+    truncated: bool,
+}
+
+impl EventBuilder {
+    const HEADER_LEN: usize = 2;
+
+    #[inline]
+    ///Creates new empty payload.
+    pub const fn new() -> Self {
+        let mut buffer = [0u8; BUFFER_CAPACITY];
+        buffer[0] = EventType::LIST as u8;
+        Self {
+            buffer,
+            len: Self::HEADER_LEN,
+            count_pos: 1,
+            truncated: false,
+        }
+    }
+
+    //Appends encoded value, if it fits, to current list.
+    fn push(&mut self, kind: EventType, data: &[&[u8]]) -> bool {
+        let len = 1 + data.iter().map(|data| data.len()).sum::<usize>();
+        if self.buffer[self.count_pos] == LIST_MAX_LEN || len > BUFFER_CAPACITY - self.len {
+            self.truncated = true;
+            return false;
+        }
+
+        self.buffer[self.len] = kind as u8;
+        self.len += 1;
+        for data in data {
+            self.buffer[self.len..self.len + data.len()].copy_from_slice(data);
+            self.len += data.len();
+        }
+        self.buffer[self.count_pos] += 1;
+        true
+    }
+
+    #[inline]
+    ///Appends integer.
+    pub fn int(&mut self, value: i32) -> &mut Self {
+        self.push(EventType::INT, &[&value.to_le_bytes()]);
+        self
+    }
+
+    #[inline]
+    ///Appends long integer.
+    pub fn long(&mut self, value: i64) -> &mut Self {
+        self.push(EventType::LONG, &[&value.to_le_bytes()]);
+        self
+    }
+
+    #[inline]
+    ///Appends float.
+    pub fn float(&mut self, value: f32) -> &mut Self {
+        self.push(EventType::FLOAT, &[&value.to_le_bytes()]);
+        self
+    }
+
+    ///Appends string, truncated to fit remaining space without splitting UTF-8 characters.
+    pub fn string(&mut self, value: &str) -> &mut Self {
+        let available = BUFFER_CAPACITY.saturating_sub(self.len + 5);
+        let mut len = core::cmp::min(value.len(), available);
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        if len < value.len() {
+            self.truncated = true;
+        }
+        self.push(EventType::STRING, &[&(len as u32).to_le_bytes(), &value.as_bytes()[..len]]);
+        self
+    }
+
+    ///Appends list with values added by `cb`.
+    pub fn list<F: FnOnce(&mut Self)>(&mut self, cb: F) -> &mut Self {
+        if self.push(EventType::LIST, &[&[0]]) {
+            let count_pos = self.count_pos;
+            self.count_pos = self.len - 1;
+            cb(self);
+            self.count_pos = count_pos;
+        }
+        self
+    }
+
+    ///Appends `value`.
+    pub fn value(&mut self, value: &EventValue<'_>) -> &mut Self {
+        match value {
+            EventValue::Int(value) => self.int(*value),
+            EventValue::Long(value) => self.long(*value),
+            EventValue::Float(value) => self.float(*value),
+            EventValue::String(value) => self.string(value),
+            EventValue::List(values) => self.list(|list| for value in values.iter() {
+                list.value(value);
+            }),
+        }
+    }
+
+    #[inline]
+    ///Returns whether some values were skipped or truncated due to payload limits.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    #[inline]
+    ///Returns encoded payload.
+    ///
+    ///Empty payload is encoded as empty list.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.buffer[1] {
+            1 => &self.buffer[Self::HEADER_LEN..self.len],
+            _ => &self.buffer[..self.len],
+        }
+    }
+
+    #[inline]
+    ///Writes event with encoded payload.
+    pub fn write(&self, tag: u32) -> i32 {
+        write(tag, self.as_bytes())
+    }
+
+    #[inline]
+    ///Writes event with encoded payload into security buffer, see `write_security`.
+    pub fn write_security(&self, tag: u32) -> Result<(), SecurityLogError> {
+        write_security(tag, self.as_bytes())
+    }
+}
+
+impl Default for EventBuilder {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///Default location of event tags description on device.
+pub const EVENT_TAGS_PATH: &str = "/system/etc/event-log-tags";
+
+///Entry of event tags description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventTag<'a> {
+    ///Numeric code of the event tag.
+    pub code: u32,
+    ///Name of the event tag.
+    pub name: &'a str,
+    ///Description of event's payload, if any.
+    pub format: &'a str,
+}
+
+impl<'a> EventTag<'a> {
+    ///Parses single line of event tags description.
+    ///
+    ///Returns `None` for comments, empty or malformed lines.
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let code = parts.next()?.parse().ok()?;
+        let rest = parts.next()?.trim_start();
+        let name_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (name, format) = rest.split_at(name_end);
+
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            code,
+            name,
+            format: format.trim(),
+        })
+    }
+}
+
+///Parses event tags description, skipping comments and malformed lines.
+pub fn parse_tags(text: &str) -> impl Iterator<Item = EventTag<'_>> {
+    text.lines().filter_map(EventTag::parse)
+}
+
+#[cfg(feature = "std")]
+///Lookup table of event tags loaded at runtime.
+pub struct EventTags {
+    codes: std::collections::HashMap<std::string::String, u32>,
+}
+
+#[cfg(feature = "std")]
+impl EventTags {
+    #[inline]
+    ///Loads event tags from `/system/etc/event-log-tags`.
+    pub fn load() -> std::io::Result<Self> {
+        Self::load_from(EVENT_TAGS_PATH)
+    }
+
+    ///Loads event tags from specified file.
+    pub fn load_from<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        std::fs::read_to_string(path).map(|text| Self::from_text(&text))
+    }
+
+    ///Creates lookup table out of event tags description.
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            codes: parse_tags(text).map(|tag| (tag.name.into(), tag.code)).collect(),
+        }
+    }
+
+    #[inline]
+    ///Returns code of the event tag by its name.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.codes.get(name).copied()
+    }
+
+    #[inline]
+    ///Returns number of known event tags.
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    #[inline]
+    ///Returns whether there are no known event tags.
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tags, EventBuilder, EventTag, EventType, EventValue, SecurityLogError};
+    use crate::sys::capture;
+
+    const TAGS: &str = "# The entries in this file map a sparse set of log tag numbers
+# to tag names.
+
+42 answer (to life the universe etc|3)
+314 pi
+2718 e
+1397638484 snet_event_log (subtag|3),(uid|1),(message|3)
+invalid line
+ 3000   spaced   (value|1|5)
+";
+
+    #[test]
+    fn should_parse_event_tags() {
+        let tags: Vec<_> = parse_tags(TAGS).collect();
+        assert_eq!(tags.len(), 5);
+        assert_eq!(tags[0], EventTag { code: 42, name: "answer", format: "(to life the universe etc|3)" });
+        assert_eq!(tags[1], EventTag { code: 314, name: "pi", format: "" });
+        assert_eq!(tags[3].name, "snet_event_log");
+        assert_eq!(tags[3].code, 1397638484);
+        assert_eq!(tags[4], EventTag { code: 3000, name: "spaced", format: "(value|1|5)" });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_lookup_event_tags() {
+        let tags = super::EventTags::from_text(TAGS);
+        assert_eq!(tags.len(), 5);
+        assert_eq!(tags.get("e"), Some(2718));
+        assert_eq!(tags.get("snet_event_log"), Some(1397638484));
+        assert_eq!(tags.get("unknown"), None);
+    }
+
+    #[test]
+    fn should_write_binary_events() {
+        super::write_int(42, 1);
+        super::write_long(43, -2);
+        super::write_float(44, 0.5);
+        super::write_str(45, "ok");
+        super::write_typed(46, EventType::INT, &[7, 0, 0, 0]);
+        super::write(47, &[0, 8, 0, 0, 0]);
+
+        let events = capture::take_events();
+        assert_eq!(events[0], (42, vec![0, 1, 0, 0, 0]));
+        assert_eq!(events[1], (43, vec![1, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]));
+        assert_eq!(events[2], (44, vec![4, 0, 0, 0, 0x3f]));
+        assert_eq!(events[3], (45, vec![2, 2, 0, 0, 0, b'o', b'k']));
+        assert_eq!(events[4], (46, vec![0, 7, 0, 0, 0]));
+        assert_eq!(events[5], (47, vec![0, 8, 0, 0, 0]));
+    }
+
+    #[test]
+    fn should_truncate_string_event() {
+        let text = "Ω".repeat(crate::BUFFER_CAPACITY);
+        super::write_str(1, &text);
+
+        let events = capture::take_events();
+        let payload = &events[0].1;
+        assert_eq!(payload.len(), crate::BUFFER_CAPACITY - 1);
+        assert_eq!(&payload[1..5], &((crate::BUFFER_CAPACITY - 6) as u32).to_le_bytes());
+    }
+
+    #[test]
+    fn should_encode_event_values() {
+        let mut event = EventBuilder::new();
+        assert_eq!(event.as_bytes(), [3, 0]);
+
+        event.int(1);
+        assert_eq!(event.as_bytes(), [0, 1, 0, 0, 0]);
+
+        event.string("a");
+        assert_eq!(event.as_bytes(), [3, 2, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, b'a']);
+
+        let mut event = EventBuilder::new();
+        event.list(|list| {
+            list.long(-1).list(|list| {
+                list.float(1.0);
+            });
+        }).int(2);
+        assert_eq!(event.as_bytes(), [3, 2, 3, 2, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 3, 1, 4, 0, 0, 0x80, 0x3f, 0, 2, 0, 0, 0]);
+        assert!(!event.is_truncated());
+
+        let values = [EventValue::Long(-1), EventValue::List(&[EventValue::Float(1.0)])];
+        let mut from_values = EventBuilder::new();
+        from_values.value(&EventValue::List(&values)).value(&2.into());
+        assert_eq!(from_values.as_bytes(), event.as_bytes());
+
+        EventValue::from("ok").write(42);
+        assert_eq!(capture::take_events(), [(42, vec![2, 2, 0, 0, 0, b'o', b'k'])]);
+    }
+
+    #[test]
+    fn should_limit_event_payload() {
+        let mut event = EventBuilder::new();
+        event.list(|list| for _ in 0..300 {
+            list.int(1);
+        });
+        assert!(event.is_truncated());
+        assert_eq!(&event.as_bytes()[..2], [3, 255]);
+        assert_eq!(event.as_bytes().len(), 2 + 255 * 5);
+
+        let mut event = EventBuilder::new();
+        event.string(&"a".repeat(crate::BUFFER_CAPACITY));
+        assert!(event.is_truncated());
+        assert_eq!(event.as_bytes().len(), crate::BUFFER_CAPACITY - 2);
+        //Nothing else fits
+        event.int(1);
+        assert_eq!(event.as_bytes()[0], EventType::STRING as u8);
+        assert_eq!(event.as_bytes().len(), crate::BUFFER_CAPACITY - 2);
+    }
+
+    #[test]
+    fn should_write_security_event() {
+        let mut event = EventBuilder::new();
+        event.string("adb");
+        assert_eq!(event.write_security(210001), Ok(()));
+        assert_eq!(capture::take_security_events(), [(210001, vec![2, 3, 0, 0, 0, b'a', b'd', b'b'])]);
+        assert!(capture::take_events().is_empty());
+
+        assert_eq!(SecurityLogError::from_result(1), Ok(()));
+        assert_eq!(SecurityLogError::from_result(-1), Err(SecurityLogError::PermissionDenied));
+        assert_eq!(SecurityLogError::from_result(-13), Err(SecurityLogError::PermissionDenied));
+        assert_eq!(SecurityLogError::from_result(-22), Err(SecurityLogError::Failed(22)));
+    }
+}
@@ -0,0 +1,213 @@
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+
+static LAST_OK: AtomicBool = AtomicBool::new(true);
+static LAST_ERROR: AtomicI32 = AtomicI32::new(0);
+static LAST_FAILURE_MS: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///State of liblog writes.
+pub struct Health {
+    ///Whether last write succeeded.
+    pub last_write_ok: bool,
+    ///Error code of last failed write, if any.
+    ///
+    ///It is negative `errno` as returned by liblog.
+    pub last_error: Option<i32>,
+    ///Time of last failure, in milliseconds since UNIX epoch.
+    ///
+    ///Available only with `std` feature.
+    pub last_failure_ms: Option<u64>,
+    ///Total number of failed writes.
+    pub failures: usize,
+}
+
+impl Health {
+    #[inline(always)]
+    ///Returns whether logging appears to work.
+    pub const fn is_healthy(&self) -> bool {
+        self.last_write_ok
+    }
+}
+
+///Returns state of liblog writes since start.
+///
+///Allows to detect when messages are silently going nowhere, for example due to `logd` being unavailable.
+pub fn health() -> Health {
+    let last_error = LAST_ERROR.load(Ordering::Relaxed);
+    let last_failure_ms = LAST_FAILURE_MS.load(Ordering::Relaxed);
+
+    Health {
+        last_write_ok: LAST_OK.load(Ordering::Relaxed),
+        last_error: if last_error == 0 { None } else { Some(last_error) },
+        last_failure_ms: if last_failure_ms == 0 { None } else { Some(last_failure_ms) },
+        failures: FAILURES.load(Ordering::Relaxed),
+    }
+}
+
+//Returned by liblog when message is filtered out by `log.tag` properties or minimum priority.
+const FILTERED: i32 = -1;
+
+#[inline(always)]
+//Returns whether `result` indicates failure to deliver message, rather than its filtering.
+const fn is_failure(result: i32) -> bool {
+    result < 0 && result != FILTERED
+}
+
+#[inline]
+pub(crate) fn record(result: i32) {
+    if result >= 0 {
+        LAST_OK.store(true, Ordering::Relaxed);
+    } else if is_failure(result) {
+        record_failure(result);
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn record_failure(result: i32) {
+    LAST_OK.store(false, Ordering::Relaxed);
+    LAST_ERROR.store(result, Ordering::Relaxed);
+    FAILURES.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "std")]
+    if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        LAST_FAILURE_MS.store(now.as_millis() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn now_ms() -> u64 {
+    match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(now) => now.as_millis() as u64,
+        Err(_) => 0,
+    }
+}
+
+#[cfg(feature = "std")]
+//Switches to stderr after consecutive liblog failures, retrying liblog periodically.
+pub(crate) struct Failover {
+    consecutive: AtomicUsize,
+    retry_at_ms: AtomicU64,
+}
+
+#[cfg(feature = "std")]
+impl Failover {
+    const THRESHOLD: usize = 3;
+    const RETRY_INTERVAL_MS: u64 = 10_000;
+
+    pub(crate) const fn new() -> Self {
+        Self {
+            consecutive: AtomicUsize::new(0),
+            retry_at_ms: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    //Returns whether liblog should be skipped in favor of stderr.
+    pub(crate) fn is_active(&self, now_ms: impl FnOnce() -> u64) -> bool {
+        self.consecutive.load(Ordering::Relaxed) >= Self::THRESHOLD && now_ms() < self.retry_at_ms.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn record(&self, result: i32, now_ms: impl FnOnce() -> u64) {
+        if !is_failure(result) {
+            if self.consecutive.load(Ordering::Relaxed) != 0 {
+                self.consecutive.store(0, Ordering::Relaxed);
+            }
+        } else if self.consecutive.fetch_add(1, Ordering::Relaxed) + 1 >= Self::THRESHOLD {
+            self.retry_at_ms.store(now_ms() + Self::RETRY_INTERVAL_MS, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+static FAILOVER: Failover = Failover::new();
+
+#[cfg(feature = "std")]
+//Writes message via `write`, falling back to stderr when it fails.
+//
+//After 3 consecutive failures stderr is used exclusively, while liblog is retried every 10 seconds.
+pub(crate) fn write_with_failover(prio: i32, tag: &core::ffi::CStr, text: &core::ffi::CStr, write: impl FnOnce() -> i32) {
+    if FAILOVER.is_active(now_ms) {
+        crate::sys::write_stderr(prio, tag.to_bytes(), text.to_bytes());
+        return;
+    }
+
+    let result = write();
+    record(result);
+    FAILOVER.record(result, now_ms);
+    if is_failure(result) {
+        crate::sys::write_stderr(prio, tag.to_bytes(), text.to_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_track_write_failures() {
+        let before = super::health();
+
+        //Other tests write concurrently, so only failure details are stable
+        super::record(-5);
+        let failed = super::health();
+        assert_eq!(failed.last_error, Some(-5));
+        assert_eq!(failed.failures, before.failures + 1);
+        #[cfg(feature = "std")]
+        assert!(failed.last_failure_ms.is_some());
+
+        super::record(10);
+        assert_eq!(super::health().last_error, Some(-5));
+
+        //Filtered message is not a failure
+        let before = super::health().failures;
+        super::record(-1);
+        let filtered = super::health();
+        assert_eq!(filtered.last_error, Some(-5));
+        assert_eq!(filtered.failures, before);
+        assert!(filtered.last_write_ok);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_failover_after_consecutive_failures() {
+        let failover = super::Failover::new();
+
+        failover.record(-111, || 0);
+        failover.record(-111, || 0);
+        assert!(!failover.is_active(|| 0));
+        failover.record(1, || 0);
+        failover.record(-111, || 0);
+        failover.record(-111, || 0);
+        assert!(!failover.is_active(|| 0));
+
+        failover.record(-111, || 1000);
+        assert!(failover.is_active(|| 1000));
+        assert!(failover.is_active(|| 10_999));
+        //Time to retry
+        assert!(!failover.is_active(|| 11_000));
+
+        failover.record(-111, || 11_000);
+        assert!(failover.is_active(|| 11_000));
+        failover.record(1, || 21_000);
+        assert!(!failover.is_active(|| 21_000));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_not_failover_on_filtered_messages() {
+        let failover = super::Failover::new();
+
+        for _ in 0..super::Failover::THRESHOLD * 2 {
+            failover.record(-1, || 0);
+        }
+        assert!(!failover.is_active(|| 0));
+
+        failover.record(-111, || 0);
+        failover.record(-111, || 0);
+        failover.record(-1, || 0);
+        failover.record(-111, || 0);
+        assert!(!failover.is_active(|| 0));
+    }
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+use core::time::Duration;
+
+#[inline(always)]
+//Returns `value / unit` rounded to tenths.
+const fn tenths(value: u128, unit: u128) -> u128 {
+    (value * 10 + unit / 2) / unit
+}
+
+//Writes number of tenths, omitting zero fraction.
+fn write_tenths(fmt: &mut fmt::Formatter<'_>, tenths: u128, suffix: &str) -> fmt::Result {
+    match tenths % 10 {
+        0 => write!(fmt, "{}{}", tenths / 10, suffix),
+        fraction => write!(fmt, "{}.{}{}", tenths / 10, fraction, suffix),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///Duration written in largest fitting unit, like `1.2s` or `2m 3s`.
+///
+///```rust
+///use androidy_log::HumanDuration;
+///use core::time::Duration;
+///
+///assert_eq!(HumanDuration(Duration::from_millis(1234)).to_string(), "1.2s");
+///assert_eq!(HumanDuration(Duration::from_secs(123)).to_string(), "2m 3s");
+///```
+pub struct HumanDuration(pub Duration);
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        let secs = self.0.as_secs();
+
+        //Unit is chosen after rounding, so that `999.96ms` is written as `1s`
+        if nanos < 1_000 {
+            write!(fmt, "{}ns", nanos)
+        } else if tenths(nanos, 1_000) < 10_000 {
+            write_tenths(fmt, tenths(nanos, 1_000), "µs")
+        } else if tenths(nanos, 1_000_000) < 10_000 {
+            write_tenths(fmt, tenths(nanos, 1_000_000), "ms")
+        } else if tenths(nanos, 1_000_000_000) < 600 {
+            write_tenths(fmt, tenths(nanos, 1_000_000_000), "s")
+        } else if secs < 3600 {
+            //Less than a minute, if rounded up
+            let secs = core::cmp::max(secs, 60);
+            write!(fmt, "{}m {}s", secs / 60, secs % 60)
+        } else {
+            write!(fmt, "{}h {}m", secs / 3600, secs % 3600 / 60)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///Number of bytes written in largest fitting binary unit, like `3.4 MiB`.
+///
+///```rust
+///use androidy_log::HumanBytes;
+///
+///assert_eq!(HumanBytes(512).to_string(), "512 B");
+///assert_eq!(HumanBytes(3_565_158).to_string(), "3.4 MiB");
+///```
+pub struct HumanBytes(pub u64);
+
+impl fmt::Display for HumanBytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 6] = [" KiB", " MiB", " GiB", " TiB", " PiB", " EiB"];
+
+        if self.0 < 1024 {
+            return write!(fmt, "{} B", self.0);
+        }
+
+        let mut unit = 1024u128;
+        let mut idx = 0;
+        while idx + 1 < UNITS.len() && tenths(self.0 as u128, unit) >= 10_240 {
+            unit *= 1024;
+            idx += 1;
+        }
+        write_tenths(fmt, tenths(self.0 as u128, unit), UNITS[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HumanBytes, HumanDuration};
+    use core::time::Duration;
+
+    #[test]
+    fn should_format_duration() {
+        assert_eq!(HumanDuration(Duration::ZERO).to_string(), "0ns");
+        assert_eq!(HumanDuration(Duration::from_nanos(1500)).to_string(), "1.5µs");
+        assert_eq!(HumanDuration(Duration::from_micros(12_000)).to_string(), "12ms");
+        assert_eq!(HumanDuration(Duration::from_millis(59_940)).to_string(), "59.9s");
+        assert_eq!(HumanDuration(Duration::from_millis(59_960)).to_string(), "1m 0s");
+        assert_eq!(HumanDuration(Duration::from_nanos(999_949)).to_string(), "999.9µs");
+        assert_eq!(HumanDuration(Duration::from_nanos(999_950)).to_string(), "1ms");
+        assert_eq!(HumanDuration(Duration::from_nanos(999_999_999)).to_string(), "1s");
+        assert_eq!(HumanDuration(Duration::from_secs(60)).to_string(), "1m 0s");
+        assert_eq!(HumanDuration(Duration::from_secs(3599)).to_string(), "59m 59s");
+        assert_eq!(HumanDuration(Duration::from_secs(7384)).to_string(), "2h 3m");
+    }
+
+    #[test]
+    fn should_format_bytes() {
+        assert_eq!(HumanBytes(0).to_string(), "0 B");
+        assert_eq!(HumanBytes(1024).to_string(), "1 KiB");
+        assert_eq!(HumanBytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(HumanBytes((1 << 20) - 1).to_string(), "1 MiB");
+        assert_eq!(HumanBytes(5 << 30).to_string(), "5 GiB");
+        assert_eq!(HumanBytes(u64::MAX).to_string(), "16 EiB");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Instant, LogPriority, TagHandle, Writer};
+
+use core::fmt::{self, Write};
+
+#[doc(hidden)]
+//State of function instrumented by `#[log_instrument]`.
+pub struct __Instrument {
+    tag: Option<TagHandle>,
+    prio: LogPriority,
+    name: &'static str,
+    start: Instant,
+}
+
+impl __Instrument {
+    pub fn enter(tag: Option<TagHandle>, prio: LogPriority, name: &'static str, args: fmt::Arguments<'_>) -> Self {
+        let instrument = Self {
+            tag,
+            prio,
+            name,
+            start: Instant::now(),
+        };
+        instrument.write(prio, format_args!("-> {}({})", name, args));
+        instrument
+    }
+
+    pub fn error(&self, error: &dyn fmt::Debug) {
+        self.write(LogPriority::ERROR, format_args!("<- {} failed: {:?}", self.name, error));
+    }
+
+    pub fn exit(self, ret: Option<&dyn fmt::Debug>) {
+        let elapsed = self.start.elapsed();
+        match ret {
+            Some(ret) => self.write(self.prio, format_args!("<- {} = {:?} +{:?}", self.name, ret, elapsed)),
+            None => self.write(self.prio, format_args!("<- {} +{:?}", self.name, elapsed)),
+        }
+    }
+
+    fn write(&self, prio: LogPriority, args: fmt::Arguments<'_>) {
+        let is_loggable = match &self.tag {
+            Some(tag) => crate::is_loggable(prio, tag.as_str()),
+            None => crate::__is_default_loggable(prio),
+        };
+        if !is_loggable {
+            return;
+        }
+
+        let _ = match &self.tag {
+            Some(tag) => tag.writer(prio).write_fmt(args),
+            None => Writer::new_default(prio).write_fmt(args),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[androidy_log::log_instrument(tag = "Instrument", prio = INFO, args(host, port), ret, err)]
+    fn connect(host: &str, port: u16) -> Result<u16, &'static str> {
+        if port == 0 {
+            return Err("invalid port");
+        }
+        Ok(port)
+    }
+
+    #[androidy_log::log_instrument(tag = "Instrument")]
+    fn generic<F>(cb: F) where F: Fn(u8) -> u8 {
+        cb(1);
+    }
+
+    #[test]
+    fn should_log_instrumented_function() {
+        assert_eq!(connect("localhost", 80), Ok(80));
+        assert_eq!(connect("localhost", 0), Err("invalid port"));
+        generic(|value| value);
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 7);
+        assert_eq!(logs[0], (LogPriority::INFO as i32, "Instrument".into(), "-> connect(host=\"localhost\", port=80)".into()));
+        assert!(logs[1].2.starts_with("<- connect = Ok(80) +"));
+        assert_eq!(logs[3], (LogPriority::ERROR as i32, "Instrument".into(), "<- connect failed: \"invalid port\"".into()));
+        assert!(logs[4].2.starts_with("<- connect = Err(\"invalid port\") +"));
+        assert_eq!(logs[5], (LogPriority::DEBUG as i32, "Instrument".into(), "-> generic()".into()));
+        assert!(logs[6].2.starts_with("<- generic +"));
+    }
+}
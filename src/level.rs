@@ -0,0 +1,162 @@
+use crate::LogPriority;
+use crate::sys;
+
+use core::sync::atomic::{AtomicI32, Ordering};
+
+static MIN_PRIORITY: AtomicI32 = AtomicI32::new(LogPriority::UNKNOWN as i32);
+
+#[inline]
+///Sets minimum priority of messages to write.
+///
+///Messages with lower priority are discarded.
+///On Android 11+ it also sets process-wide minimum priority of liblog via `__android_log_set_minimum_priority`,
+///which applies to messages written by other code in the process, while on older versions only
+///this crate's writers are affected.
+pub fn set_min_priority(prio: LogPriority) {
+    MIN_PRIORITY.store(prio as i32, Ordering::Relaxed);
+    sys::set_minimum_priority(prio as i32);
+}
+
+#[inline]
+///Returns minimum priority of messages to write.
+///
+///By default all messages are written.
+///On Android 11+ it also takes into account process-wide minimum priority of liblog, returned by `__android_log_get_minimum_priority`.
+pub fn min_priority() -> LogPriority {
+    let prio = MIN_PRIORITY.load(Ordering::Relaxed);
+    match sys::minimum_priority().map_or(prio, |system| prio.max(system)) {
+        1 => LogPriority::DEFAULT,
+        2 => LogPriority::VERBOSE,
+        3 => LogPriority::DEBUG,
+        4 => LogPriority::INFO,
+        5 => LogPriority::WARN,
+        6 => LogPriority::ERROR,
+        7 => LogPriority::FATAL,
+        8 => LogPriority::SILENT,
+        _ => LogPriority::UNKNOWN,
+    }
+}
+
+#[inline(always)]
+///Returns whether message with specified priority is to be written.
+pub fn is_enabled(prio: LogPriority) -> bool {
+    prio as i32 >= MIN_PRIORITY.load(Ordering::Relaxed)
+}
+
+#[inline]
+///Returns whether message with specified priority and tag is to be written.
+///
+///In addition to `is_enabled`, on Android 11+ consults system's filter configured via `log.tag` properties.
+///Empty tag is only checked against global filter.
+///
+///Logging macros check it before formatting message.
+///
+///```rust,no_run
+///use androidy_log::LogPriority;
+///
+///if androidy_log::is_loggable(LogPriority::DEBUG, "Cache") {
+///    let stats = "expensive to collect";
+///    androidy_log::debug!(tag: "Cache", "{}", stats);
+///}
+///```
+pub fn is_loggable(prio: LogPriority, tag: &str) -> bool {
+    is_enabled(prio) && sys::is_loggable(prio as i32, &tag[..crate::tag_len(tag)])
+}
+
+#[doc(hidden)]
+///Returns whether message with specified priority and default tag is to be written.
+///
+///Default tag is the same as of `Writer::new_default`, used by logging macros without tag.
+pub fn __is_default_loggable(prio: LogPriority) -> bool {
+    if !is_enabled(prio) {
+        return false;
+    }
+
+    #[cfg(feature = "std")]
+    if let Some(is_loggable) = crate::scope::with_tag(|tag| sys::is_loggable(prio as i32, tag.as_str())) {
+        return is_loggable;
+    }
+    #[cfg(feature = "ndk-context")]
+    if let Some(tag) = crate::context::package_tag() {
+        return sys::is_loggable(prio as i32, tag.as_str());
+    }
+
+    sys::is_loggable(prio as i32, crate::DEFAULT_TAG)
+}
+
+///Returns whether build is debuggable.
+///
+///On Android it is determined by `ro.debuggable` system property, while other platforms are always
+///considered debuggable.
+pub fn is_debuggable() -> bool {
+    sys::is_debuggable()
+}
+
+#[inline]
+///Raises minimum priority to `prio` on non-debuggable builds, as determined by `is_debuggable`.
+pub fn limit_release_builds(prio: LogPriority) {
+    limit_release_builds_with(prio, is_debuggable)
+}
+
+///Raises minimum priority to `prio`, if `is_debuggable` returns `false`.
+///
+///Allows to use app's own debuggable flag instead of `ro.debuggable`.
+///Minimum priority is never lowered.
+pub fn limit_release_builds_with<F: FnOnce() -> bool>(prio: LogPriority, is_debuggable: F) {
+    if !is_debuggable() {
+        let prio = MIN_PRIORITY.fetch_max(prio as i32, Ordering::Relaxed).max(prio as i32);
+        sys::set_minimum_priority(prio);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_filter_by_min_priority() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        assert!(is_debuggable());
+        //No test logs with UNKNOWN so it is safe to filter it out.
+        limit_release_builds(LogPriority::DEFAULT);
+        assert_eq!(min_priority(), LogPriority::UNKNOWN);
+        limit_release_builds_with(LogPriority::DEFAULT, || false);
+        assert_eq!(min_priority(), LogPriority::DEFAULT);
+        limit_release_builds_with(LogPriority::UNKNOWN, || false);
+        assert_eq!(min_priority(), LogPriority::DEFAULT);
+
+        assert!(!is_enabled(LogPriority::UNKNOWN));
+        assert!(is_enabled(LogPriority::VERBOSE));
+        assert!(!is_loggable(LogPriority::UNKNOWN, "Level"));
+        assert!(is_loggable(LogPriority::VERBOSE, "ΩΩΩΩΩΩΩΩΩΩΩΩ"));
+        let mut writer = crate::Writer::new("Level", LogPriority::UNKNOWN);
+        writer.write_data(b"discarded");
+        writer.flush();
+        assert!(writer.buffer().is_empty());
+        assert!(crate::sys::capture::take().is_empty());
+        crate::log_unchecked!(UNKNOWN, "breadcrumb {}", 1);
+        assert_eq!(crate::sys::capture::take(), [(LogPriority::UNKNOWN as i32, "Rust".into(), "breadcrumb 1".into())]);
+
+        set_min_priority(LogPriority::UNKNOWN);
+        assert!(is_enabled(LogPriority::UNKNOWN));
+    }
+
+    #[test]
+    fn should_check_default_tag_to_be_written() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        crate::sys::capture::LOGGABLE_TAGS.with(|tags| tags.borrow_mut().clear());
+
+        crate::info!("default");
+        #[cfg(feature = "std")]
+        {
+            let _scope = crate::TagScope::enter("Scoped");
+            crate::info!("scoped");
+        }
+
+        let tags = crate::sys::capture::LOGGABLE_TAGS.with(|tags| tags.take());
+        assert_eq!(tags[0], crate::DEFAULT_TAG);
+        #[cfg(feature = "std")]
+        assert_eq!(tags[1], "Scoped");
+        let _ = crate::sys::capture::take();
+    }
+}
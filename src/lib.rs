@@ -2,7 +2,40 @@
 //!
 //! ## Features:
 //!
-//! - `std` - Enables `std::io::Write` implementation.
+//! - `std` - Enables `std::io::Write` implementation and utilities that require `std`, like `events::EventTags`.
+//! - `output-debug-string` - Enables `OutputDebugStringW` host fallback on Windows.
+//! - `metrics` - Enables counters of written messages per priority.
+//! - `zeroize` - Wipes internal buffer after each flush, so that logged data doesn't linger in memory.
+//! - `ndk-context` - Uses application's package name as default tag, retrieved via `ndk-context`. Implies `std`.
+//! - `macros` - Enables `#[main]` and `#[log_instrument]` attributes. Implies `std`.
+//! - `serde` - Enables `Writer::write_json` and `log_json!` to log values serialized as JSON. Implies `std`.
+//! - `debug-truncation` - Panics in debug builds when tag passed to `Writer::new` or message is truncated, or message is split due to buffer capacity.
+//! - `source-location` - Writes source file and line of logging macros and `log` records on Android 11+, shown by `logcat -v long`.
+//! - `log` - Enables backend of `log` facade, available without `std`. With `std` it can be configured via `builder()`, using `RUST_LOG` like filter.
+//! - `kv` - Writes key-values of `log` records as trailing `key=value` pairs. Implies `log`.
+//! - `tracing` - Enables `LogcatLayer` of `tracing-subscriber`, writing events with context of enclosing spans. Implies `std`.
+//! - `atrace` - Writes spans of `LogcatLayer` as ATrace sections, visible in Perfetto. Implies `tracing`.
+//! - `slog` - Enables `LogcatDrain` of `slog`, available without `std`.
+//! - `ufmt` - Implements `ufmt::uWrite` for `Writer`, allowing to avoid `core::fmt` machinery.
+//! - `tokio` and `futures-io` - Implement `AsyncWrite` of respective crate for `Writer`. Imply `std`.
+//! - `log4rs` - Enables `LogcatAppender` of `log4rs`, configurable as `kind: android` appender. Implies `log` and `std`.
+//!
+//! ## Host fallback
+//!
+//! When built for non-Android target, messages are written to stderr, if `std` is enabled, using
+//! format similar to `logcat`'s brief format: `I/Tag: message`.
+//!
+//! ## Failover
+//!
+//! With `std` feature, messages that liblog fails to write are written to stderr instead.
+//! After 3 consecutive failures liblog is skipped, and it is retried every 10 seconds.
+//!
+//! ## Minimum priority
+//!
+//! Messages below `min_priority()` are discarded.
+//! On Android 11+ it is also applied process-wide via `__android_log_set_minimum_priority`.
+//! Use `limit_release_builds(LogPriority::INFO)` at init to skip verbose logging on non-debuggable builds.
+//! On Android 11+ logging macros also respect `log.tag` system properties, checked by `is_loggable()` before formatting.
 //!
 //! ## Usage
 //!
@@ -17,6 +50,7 @@
 //!
 //! androidy_log::println!("Hello via macro!");
 //! androidy_log::eprintln!("Error via macro!");
+//! androidy_log::debug!("Debug via macro!");
 //! ```
 //!
 
@@ -27,6 +61,151 @@
 extern crate std;
 
 use core::{cmp, mem, ptr, fmt};
+use core::ffi::{c_char, CStr};
+
+mod sys;
+use sys::__android_log_write;
+mod health;
+pub use health::{health, Health};
+mod sink;
+pub use sink::{set_log_sink, LogSink};
+mod level;
+pub use level::{set_min_priority, min_priority, is_enabled, is_loggable, __is_default_loggable, is_debuggable, limit_release_builds, limit_release_builds_with};
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::{metrics, Metrics};
+pub mod events;
+pub mod pmsg;
+mod template;
+pub use template::Template;
+mod redact;
+pub use redact::{Redacted, Redaction, set_reveal_redacted};
+mod wtf;
+pub use wtf::{set_wtf_handler, WtfHandler};
+mod human;
+pub use human::{HumanDuration, HumanBytes};
+mod logfmt;
+pub use logfmt::Event;
+#[cfg(feature = "log")]
+mod logger;
+#[cfg(feature = "log")]
+pub use logger::{LogcatLogger, TargetTag, init, init_with, init_with_tag};
+#[cfg(all(feature = "log", feature = "std"))]
+mod log_builder;
+#[cfg(all(feature = "log", feature = "std"))]
+pub use log_builder::{builder, Builder, FilteredLogger};
+#[cfg(any(unix, feature = "std"))]
+mod abort;
+#[cfg(any(unix, feature = "std"))]
+pub use abort::{abort, log_assert, set_aborter};
+#[cfg(feature = "std")]
+pub mod mdc;
+#[cfg(feature = "std")]
+mod pipe;
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+pub use scope::{TagScope, set_default_tag, IndentScope, set_indent, CorrelationScope, set_correlation_id, correlation_id};
+#[cfg(feature = "std")]
+pub use pipe::{pipe_to_log, PipeStats};
+#[cfg(feature = "ndk-context")]
+mod context;
+#[cfg(feature = "ndk-context")]
+pub use context::package_tag;
+#[cfg(feature = "std")]
+mod rt;
+#[cfg(feature = "std")]
+pub use rt::{install_panic_hook, __main_init};
+#[cfg(all(feature = "std", target_os = "android"))]
+pub use rt::redirect_stdio;
+#[cfg(feature = "macros")]
+///Sets up logging at the start of entry point, like `android_main` or JNI functions.
+///
+///Before calling function's body, once per process:
+///
+///- Sets default tag, if specified as `#[main(tag = "MyApp")]`;
+///- Installs panic hook that writes panics into log;
+///- On Android, redirects stdout and stderr into log.
+///
+///```rust,no_run
+///#[androidy_log::main(tag = "MyApp")]
+///fn main() {
+///    println!("Written into log on Android");
+///}
+///```
+pub use androidy_log_macros::main;
+#[cfg(feature = "macros")]
+///Logs entry, exit and duration of function.
+///
+///Accepts following arguments, all of which are optional:
+///
+///- `tag = "..."` - Tag to use, by default `Writer::new_default` is used.
+///- `prio = ...` - Priority of messages, `DEBUG` by default.
+///- `args(name, ...)` - Arguments to log on entry, which must implement `Debug`.
+///- `ret` - Logs returned value, which must implement `Debug`.
+///- `err` - Logs `Err` of returned `Result` with `ERROR` priority.
+///
+///Async functions are not supported.
+///
+///```rust,no_run
+///#[androidy_log::log_instrument(tag = "Net", prio = INFO, args(host), ret)]
+///fn resolve(host: &str) -> usize {
+///    host.len()
+///}
+///
+///resolve("localhost");
+/////-> resolve(host="localhost")
+/////<- resolve = 9 +1.2µs
+///```
+pub use androidy_log_macros::log_instrument;
+#[cfg(all(test, feature = "macros"))]
+//Procedural macros refer to crate by its name
+extern crate self as androidy_log;
+#[cfg(feature = "macros")]
+mod instrument;
+#[cfg(feature = "macros")]
+pub use instrument::__Instrument;
+#[cfg(feature = "std")]
+mod print;
+#[cfg(feature = "std")]
+pub use print::{__print, set_print_idle_flush};
+#[cfg(feature = "std")]
+mod table;
+#[cfg(feature = "std")]
+pub use table::Table;
+#[cfg(feature = "std")]
+mod error_chain;
+#[cfg(feature = "std")]
+pub use error_chain::ErrorChain;
+#[cfg(feature = "log4rs")]
+mod log4rs_appender;
+#[cfg(feature = "log4rs")]
+pub use log4rs_appender::{LogcatAppender, LogcatAppenderConfig, LogcatAppenderDeserializer};
+#[cfg(feature = "slog")]
+mod slog_drain;
+#[cfg(feature = "slog")]
+pub use slog_drain::LogcatDrain;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::{LogcatLayer, SpanContext};
+#[cfg(any(unix, feature = "std"))]
+mod clock;
+#[cfg(any(unix, feature = "std"))]
+pub use clock::Instant;
+#[cfg(any(unix, feature = "std"))]
+mod statsd;
+#[cfg(any(unix, feature = "std"))]
+pub use statsd::{METRIC_TAG, set_metric_interval, __Metric};
+#[cfg(any(unix, feature = "std"))]
+mod stopwatch;
+#[cfg(any(unix, feature = "std"))]
+pub use stopwatch::{Stopwatch, __TimeScope};
+#[cfg(any(unix, feature = "std"))]
+mod scope_logger;
+#[cfg(any(unix, feature = "std"))]
+pub use scope_logger::ScopeLogger;
 
 ///Priority of the log message.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,41 +237,367 @@ pub enum LogPriority {
     SILENT = 8,
 }
 
+impl LogPriority {
+    ///Maps number of verbosity flags, like `-v` or `-vvv`, onto priority.
+    ///
+    ///- `0` - WARN
+    ///- `1` - INFO
+    ///- `2` - DEBUG
+    ///- `3` and more - VERBOSE
+    pub const fn from_verbosity(verbosity: u8) -> Self {
+        match verbosity {
+            0 => LogPriority::WARN,
+            1 => LogPriority::INFO,
+            2 => LogPriority::DEBUG,
+            _ => LogPriority::VERBOSE,
+        }
+    }
+}
+
+///Identifier of the log buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum LogId {
+    ///Main log buffer, used by applications.
+    MAIN = 0,
+    ///Radio log buffer, used by telephony.
+    RADIO = 1,
+    ///Event log buffer, used for binary events.
+    EVENTS = 2,
+    ///System log buffer, used by system components.
+    SYSTEM = 3,
+    ///Crash log buffer.
+    CRASH = 4,
+    ///Statistics log buffer.
+    STATS = 5,
+    ///Security log buffer.
+    SECURITY = 6,
+    ///Kernel log buffer.
+    KERNEL = 7,
+    ///Lets liblog to choose buffer.
+    DEFAULT = 0x7FFFFFFF,
+}
+
+impl LogId {
+    const ALL: [LogId; 9] = [LogId::MAIN, LogId::RADIO, LogId::EVENTS, LogId::SYSTEM, LogId::CRASH, LogId::STATS, LogId::SECURITY, LogId::KERNEL, LogId::DEFAULT];
+
+    ///Returns buffer name, as used by `logcat -b`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            LogId::MAIN => "main",
+            LogId::RADIO => "radio",
+            LogId::EVENTS => "events",
+            LogId::SYSTEM => "system",
+            LogId::CRASH => "crash",
+            LogId::STATS => "stats",
+            LogId::SECURITY => "security",
+            LogId::KERNEL => "kernel",
+            LogId::DEFAULT => "default",
+        }
+    }
+
+    ///Looks up buffer by its name, as used by `logcat -b`.
+    ///
+    ///Returns `None` if name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|id| id.name() == name).copied()
+    }
+}
+
 const TAG_MAX_LEN: usize = 23;
 //Re-check NDK sources, I think internally kernel limits to 4076, but
 //it includes some overhead of logcat machinery, hence 4000
 //Don't remember details
 const BUFFER_CAPACITY: usize = 4000;
 const DEFAULT_TAG: &str = "Rust";
+///Default tag of radio messages, used by native RIL.
+pub const RADIO_TAG: &str = "RIL";
 
-#[cfg(not(test))]
-#[link(name = "log")]
-extern "C" {
-    fn __android_log_write(prio: i32, tag: *const i8, text: *const i8) -> i32;
+#[inline(always)]
+fn report_truncation(_kind: &str) {
+    #[cfg(all(feature = "debug-truncation", debug_assertions))]
+    truncation_panic(_kind);
 }
 
-#[cfg(test)]
-fn __android_log_write(_: i32, _: *const i8, _: *const i8) -> i32 {
-    0
+#[cfg(all(feature = "debug-truncation", debug_assertions))]
+#[cold]
+#[inline(never)]
+fn truncation_panic(kind: &str) {
+    //Only tests that opt in expect panic
+    #[cfg(test)]
+    if !sys::capture::STRICT.with(|strict| strict.get()) {
+        return;
+    }
+
+    panic!("androidy-log: {}", kind);
+}
+
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+//Failure of `assert_log!`, written with FATAL priority before panic.
+pub fn __assert_failed(tag: Option<&str>, args: fmt::Arguments<'_>) -> ! {
+    let mut writer = match tag {
+        Some(tag) => Writer::new(tag, LogPriority::FATAL),
+        None => Writer::new_default(LogPriority::FATAL),
+    };
+    let _ = fmt::Write::write_fmt(&mut writer, args);
+    drop(writer);
+    panic!("{}", args)
+}
+
+#[inline]
+//Length of tag that fits into TAG_MAX_LEN, cut on char boundary.
+fn tag_len(tag: &str) -> usize {
+    let mut len = cmp::min(tag.len(), TAG_MAX_LEN);
+    while !tag.is_char_boundary(len) {
+        len -= 1;
+    }
+    len
+}
+
+///Handling of ASCII control characters, except `\n` and `\t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlChars {
+    ///Control characters are written as it is.
+    ///
+    ///This is default.
+    Keep,
+    ///Control characters are removed.
+    Strip,
+    ///Control characters are replaced with `\xNN` escape.
+    Escape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//State of ANSI escape sequence parser.
+enum AnsiState {
+    Text,
+    //After ESC
+    Escape,
+    //Control Sequence Introducer: ESC [
+    Csi,
+    //Operating System Command: ESC ]
+    Osc,
+    //ESC within OSC, possibly String Terminator
+    OscEscape,
+}
+
+impl AnsiState {
+    #[inline]
+    const fn next(self, byte: u8) -> Self {
+        match self {
+            AnsiState::Text => match byte {
+                0x1b => AnsiState::Escape,
+                _ => AnsiState::Text,
+            },
+            AnsiState::Escape => match byte {
+                b'[' => AnsiState::Csi,
+                b']' => AnsiState::Osc,
+                //Intermediate bytes
+                0x20..=0x2f => AnsiState::Escape,
+                _ => AnsiState::Text,
+            },
+            AnsiState::Csi => match byte {
+                0x40..=0x7e => AnsiState::Text,
+                _ => AnsiState::Csi,
+            },
+            AnsiState::Osc => match byte {
+                0x07 => AnsiState::Text,
+                0x1b => AnsiState::OscEscape,
+                _ => AnsiState::Osc,
+            },
+            AnsiState::OscEscape => AnsiState::Text,
+        }
+    }
+}
+
+#[inline(always)]
+fn is_char_boundary(text: &[u8], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+
+    match text.get(idx) {
+        None => idx == text.len(),
+        Some(&byte) => (byte as i8) >= -0x40
+    }
+}
+
+///Handling of data that doesn't fit into buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    ///Buffer is flushed and the rest of data is written into it.
+    ///
+    ///This is default.
+    Chunk,
+    ///Message is truncated on char boundary and marked with `…`.
+    ///
+    ///Data is discarded until buffer is flushed.
+    TruncateWithEllipsis,
+}
+
+#[inline(always)]
+const fn is_control_char(byte: u8) -> bool {
+    (byte < 0x20 && byte != b'\n' && byte != b'\t') || byte == 0x7f
+}
+
+//Writes decimal digits of `value` at the start of `out`, returning their number.
+fn format_uint(mut value: u64, out: &mut [u8]) -> usize {
+    let mut digits = [0u8; 20];
+    let mut idx = digits.len();
+    loop {
+        idx -= 1;
+        digits[idx] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+
+    let len = digits.len() - idx;
+    out[..len].copy_from_slice(&digits[idx..]);
+    len
+}
+
+///Tag storage of `Writer`.
+///
+///# Safety
+///
+///Implementation must always return pointer to valid C string, that lives as long as `self`, or null pointer.
+pub unsafe trait AsTag {
+    ///Returns pointer to null terminated tag.
+    ///
+    ///Null pointer lets logd to use process's default tag.
+    fn as_tag_ptr(&self) -> *const c_char;
+}
+
+///Tag stored inline, used by default `Writer`.
+pub type TagBuf = mem::MaybeUninit<[u8; TAG_MAX_LEN + 1]>;
+
+#[doc(hidden)]
+//Validates tag at compile time, used by `tag!`.
+pub const fn __checked_tag(tag: &str) -> TagBuf {
+    let tag = tag.as_bytes();
+    if tag.len() > TAG_MAX_LEN {
+        panic!("tag exceeds 23 bytes");
+    }
+
+    let mut buffer = [0u8; TAG_MAX_LEN + 1];
+    let mut idx = 0;
+    while idx < tag.len() {
+        if tag[idx] == 0 {
+            panic!("tag contains null character");
+        }
+        buffer[idx] = tag[idx];
+        idx += 1;
+    }
+
+    mem::MaybeUninit::new(buffer)
+}
+
+unsafe impl AsTag for TagBuf {
+    #[inline(always)]
+    fn as_tag_ptr(&self) -> *const c_char {
+        self.as_ptr() as _
+    }
+}
+
+unsafe impl AsTag for CStr {
+    #[inline(always)]
+    fn as_tag_ptr(&self) -> *const c_char {
+        self.as_ptr()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+///Tag, that is passed to liblog as null, letting logd to attribute entries to process's default tag.
+///
+///Useful for libraries, that would rather not impose own tag.
+///Host fallback writes such entries with empty tag.
+///
+///```rust,no_run
+///use androidy_log::{LogPriority, NullTag, Writer};
+///
+///let mut writer = Writer::with_tag(NullTag, LogPriority::INFO);
+///writer.write_data(b"Logged under process's default tag");
+///```
+pub struct NullTag;
+
+unsafe impl AsTag for NullTag {
+    #[inline(always)]
+    fn as_tag_ptr(&self) -> *const c_char {
+        ptr::null()
+    }
+}
+
+unsafe impl<T: AsTag + ?Sized> AsTag for &T {
+    #[inline(always)]
+    fn as_tag_ptr(&self) -> *const c_char {
+        T::as_tag_ptr(self)
+    }
 }
 
 ///Android log writer.
 ///
 ///By default every write is buffer unless buffer overflow happens.
 ///Buffered input is flushed on `Drop` or via manual call.
-pub struct Writer {
-    //Null character is not within limit
-    tag: mem::MaybeUninit<[u8; TAG_MAX_LEN + 1]>,
+///
+///Tag is stored inline by default, but it can be any `AsTag`, see `StaticWriter`.
+pub struct Writer<T: AsTag = TagBuf> {
+    tag: T,
+    default_tag: bool,
     prio: LogPriority,
+    log_id: LogId,
+    #[cfg(feature = "source-location")]
+    location: Option<(&'static str, u32)>,
     //Null character is not within limit
     buffer: mem::MaybeUninit<[u8; BUFFER_CAPACITY + 1]>,
     len: usize,
+    options: Options,
+    ansi: AnsiState,
+    truncated: bool,
+    after_cr: bool,
+}
+
+#[derive(Clone, Copy)]
+//Options of `Writer`, which are rarely changed from defaults.
+struct Options {
+    control_chars: ControlChars,
+    overflow: Overflow,
+    //Never exceeds buffer capacity
+    flush_threshold: u16,
+    strip_ansi: bool,
+    line_buffered: bool,
+    normalize_newlines: bool,
+    #[cfg(feature = "std")]
+    partial_writes: bool,
+    //Ignores minimum priority
+    unchecked: bool,
+}
+
+impl Options {
+    const DEFAULT: Self = Self {
+        control_chars: ControlChars::Keep,
+        overflow: Overflow::Chunk,
+        flush_threshold: BUFFER_CAPACITY as u16,
+        strip_ansi: false,
+        line_buffered: false,
+        normalize_newlines: false,
+        #[cfg(feature = "std")]
+        partial_writes: false,
+        unchecked: false,
+    };
 }
 
 impl Writer {
     #[inline(always)]
     ///Creates new instance using default tag `Rust`
     ///
+    ///With `std` feature, default tag can be overridden by `TagScope` for current thread.
+    ///
+    ///With `ndk-context` feature, application's package name is used instead of `Rust`, if available.
+    ///
     ///- `prio` - Logging priority.
     pub const fn new_default(prio: LogPriority) -> Self {
         let mut tag = [0u8; TAG_MAX_LEN + 1];
@@ -101,38 +606,301 @@ impl Writer {
         tag[1] = DEFAULT_TAG.as_bytes()[1];
         tag[2] = DEFAULT_TAG.as_bytes()[2];
         tag[3] = DEFAULT_TAG.as_bytes()[3];
-        unsafe {
+        let mut writer = unsafe {
             Self::from_raw_parts(mem::MaybeUninit::new(tag), prio)
-        }
+        };
+        writer.default_tag = true;
+        writer
     }
 
     #[inline]
     ///Creates new instance using:
     ///
-    ///- `tag` - Log message tag, truncated to first 23 characters.
+    ///- `tag` - Log message tag, truncated to first 23 bytes without splitting UTF-8 characters.
     ///- `prio` - Logging priority
     pub fn new(tag: &str, prio: LogPriority) -> Self {
+        if tag.len() > TAG_MAX_LEN {
+            report_truncation("tag exceeds 23 bytes and is truncated");
+        }
+
         let mut tag_buffer = mem::MaybeUninit::<[u8; TAG_MAX_LEN + 1]>::zeroed();
         unsafe {
-            ptr::copy_nonoverlapping(tag.as_ptr(), tag_buffer.as_mut_ptr() as *mut u8, cmp::min(tag.len(), TAG_MAX_LEN));
+            ptr::copy_nonoverlapping(tag.as_ptr(), tag_buffer.as_mut_ptr() as *mut u8, tag_len(tag));
             Self::from_raw_parts(tag_buffer, prio)
         }
     }
 
+    #[inline]
+    ///Creates new instance writing into specified log buffer, using:
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes without splitting UTF-8 characters.
+    ///- `prio` - Logging priority
+    ///- `log_id` - Log buffer, see `Writer::set_log_id`
+    pub fn new_with_id(tag: &str, prio: LogPriority, log_id: LogId) -> Self {
+        let mut writer = Self::new(tag, prio);
+        writer.log_id = log_id;
+        writer
+    }
+
+    #[inline]
+    ///Creates new instance writing into `LogId::RADIO` buffer, shown by `adb logcat -b radio`.
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes. Telephony components use `RADIO_TAG` by default.
+    ///- `prio` - Logging priority
+    pub fn new_radio(tag: &str, prio: LogPriority) -> Self {
+        Self::new_with_id(tag, prio, LogId::RADIO)
+    }
+
+    #[inline]
+    ///Creates new instance writing into `LogId::SYSTEM` buffer, shown by `adb logcat -b system`.
+    ///
+    ///Intended for system services, keeping their output separate from applications' logs.
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes.
+    ///- `prio` - Logging priority
+    pub fn new_system(tag: &str, prio: LogPriority) -> Self {
+        Self::new_with_id(tag, prio, LogId::SYSTEM)
+    }
+
+    #[inline]
+    ///Creates new instance writing into `LogId::CRASH` buffer, shown by `adb logcat -b crash` and included in bugreports.
+    ///
+    ///Intended for final diagnostics of panic or abort, that would otherwise be lost in busy main buffer.
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes.
+    ///- `prio` - Logging priority
+    pub fn new_crash(tag: &str, prio: LogPriority) -> Self {
+        Self::new_with_id(tag, prio, LogId::CRASH)
+    }
+
     #[inline]
     ///Creates new instance with:
     ///
     ///- `tag` - Log message's tag as raw C string, that must be ending with 0. It is UB to pass anything else.
     ///- `prio` - Logging priority
-    pub const unsafe fn from_raw_parts(tag: mem::MaybeUninit<[u8; TAG_MAX_LEN + 1]>, prio: LogPriority) -> Self {
+    ///
+    ///# Safety
+    ///
+    ///`tag` must contain null character.
+    pub const unsafe fn from_raw_parts(tag: TagBuf, prio: LogPriority) -> Self {
+        Self::with_tag(tag, prio)
+    }
+}
+
+///Prepared, null terminated tag, that can be shared by multiple writers.
+///
+///```rust,no_run
+///use androidy_log::{LogPriority, TagHandle};
+///
+///use core::fmt::Write;
+///
+///static TAG: TagHandle = TagHandle::new("Network");
+///
+///let mut writer = TAG.writer(LogPriority::INFO);
+///let _ = write!(writer, "Connected");
+///```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TagHandle([u8; TAG_MAX_LEN + 1]);
+
+impl TagHandle {
+    ///Creates new tag, truncated to first 23 bytes without splitting UTF-8 characters.
+    pub const fn new(tag: &str) -> Self {
+        let tag = tag.as_bytes();
+        let mut len = if tag.len() > TAG_MAX_LEN { TAG_MAX_LEN } else { tag.len() };
+        while len < tag.len() && (tag[len] as i8) < -0x40 {
+            len -= 1;
+        }
+
+        let mut buffer = [0u8; TAG_MAX_LEN + 1];
+        let mut idx = 0;
+        while idx < len {
+            buffer[idx] = tag[idx];
+            idx += 1;
+        }
+
+        Self(buffer)
+    }
+
+    #[inline]
+    ///Returns tag as C string.
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe {
+            CStr::from_ptr(self.0.as_ptr() as _)
+        }
+    }
+
+    #[inline]
+    ///Returns tag as string.
+    pub fn as_str(&self) -> &str {
+        //Created from str and cut on char boundary
+        unsafe {
+            core::str::from_utf8_unchecked(self.as_c_str().to_bytes())
+        }
+    }
+
+    #[inline(always)]
+    ///Returns raw tag suitable for `Writer::from_raw_parts`.
+    pub const fn into_raw(self) -> TagBuf {
+        mem::MaybeUninit::new(self.0)
+    }
+
+    #[inline(always)]
+    ///Creates new writer referring to this tag.
+    pub const fn writer(&self, prio: LogPriority) -> Writer<&Self> {
+        Writer::with_tag(self, prio)
+    }
+}
+
+unsafe impl AsTag for TagHandle {
+    #[inline(always)]
+    fn as_tag_ptr(&self) -> *const c_char {
+        self.0.as_ptr() as _
+    }
+}
+
+///Writer that refers to static tag instead of storing its copy.
+pub type StaticWriter = Writer<&'static CStr>;
+
+impl StaticWriter {
+    #[inline(always)]
+    ///Creates new instance using:
+    ///
+    ///- `tag` - Log message tag, it is not truncated by this writer.
+    ///- `prio` - Logging priority
+    pub const fn new_static(tag: &'static CStr, prio: LogPriority) -> Self {
+        Self::with_tag(tag, prio)
+    }
+}
+
+impl<T: AsTag> Writer<T> {
+    #[inline]
+    ///Creates new instance with:
+    ///
+    ///- `tag` - Log message's tag storage.
+    ///- `prio` - Logging priority
+    pub const fn with_tag(tag: T, prio: LogPriority) -> Self {
         Self {
             tag,
+            default_tag: false,
             prio,
+            log_id: LogId::MAIN,
+            #[cfg(feature = "source-location")]
+            location: None,
             buffer: mem::MaybeUninit::uninit(),
             len: 0,
+            options: Options::DEFAULT,
+            ansi: AnsiState::Text,
+            truncated: false,
+            after_cr: false,
         }
     }
 
+    #[inline(always)]
+    ///Sets log buffer to write into.
+    ///
+    ///By default writer uses `LogId::MAIN` via `__android_log_write`.
+    ///Other buffers are written via `__android_log_buf_write`, which might be restricted to system components.
+    pub fn set_log_id(&mut self, log_id: LogId) {
+        self.log_id = log_id;
+    }
+
+    #[inline(always)]
+    ///Returns log buffer to write into.
+    pub fn log_id(&self) -> LogId {
+        self.log_id
+    }
+
+    #[cfg(feature = "source-location")]
+    #[inline(always)]
+    ///Sets source location of messages.
+    ///
+    ///On Android 11+ it is written via `__android_log_write_log_message`, otherwise it is ignored.
+    ///Note that liblog doesn't report failures of such writes, so they are not visible in `health()` and do not trigger
+    ///failover to stderr.
+    ///
+    ///Requires `source-location` feature.
+    pub fn set_location(&mut self, file: &'static str, line: u32) {
+        self.location = Some((file, line));
+    }
+
+    #[cfg(feature = "source-location")]
+    #[inline(always)]
+    #[track_caller]
+    ///Sets source location of messages to location of the caller, see `set_location`.
+    ///
+    ///Requires `source-location` feature.
+    pub fn set_caller_location(&mut self) {
+        let location = core::panic::Location::caller();
+        self.set_location(location.file(), location.line());
+    }
+
+    #[inline(always)]
+    ///Sets handling of data that doesn't fit into buffer.
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.options.overflow = overflow;
+    }
+
+    #[inline(always)]
+    ///Sets number of buffered bytes, exceeding which buffer is flushed at the end of write.
+    ///
+    ///Lower values reduce latency between writing and visibility in logcat, at cost of more entries.
+    ///By default buffer is flushed only when it overflows.
+    pub fn set_flush_threshold(&mut self, threshold: usize) {
+        self.options.flush_threshold = cmp::min(threshold, BUFFER_CAPACITY) as u16;
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    ///Sets whether `std::io::Write::write` should only consume data that fits into buffer.
+    ///
+    ///When enabled, `write` returns number of bytes that fit before buffer would need to be flushed.
+    ///If nothing fits, buffer is flushed first.
+    ///Otherwise `write` always consumes all data, flushing buffer as needed.
+    ///
+    ///Number of bytes is calculated from input, before sanitization or ANSI sequences stripping.
+    pub fn set_partial_writes(&mut self, partial_writes: bool) {
+        self.options.partial_writes = partial_writes;
+    }
+
+    #[inline(always)]
+    ///Sets whether buffer should be flushed on every `\n`.
+    ///
+    ///When enabled, every line is written as its own entry, without `\n` itself.
+    pub fn set_line_buffered(&mut self, line_buffered: bool) {
+        self.options.line_buffered = line_buffered;
+    }
+
+    #[inline(always)]
+    ///Sets whether `\r\n` and lone `\r` should be replaced with `\n`.
+    ///
+    ///Normalization happens before any other processing, including line buffering.
+    ///`\r\n` may span across multiple writes.
+    pub fn set_normalize_newlines(&mut self, normalize_newlines: bool) {
+        self.options.normalize_newlines = normalize_newlines;
+        self.after_cr = false;
+    }
+
+    #[inline(always)]
+    ///Sets handling of control characters in written data.
+    ///
+    ///Raw control characters can corrupt terminals and log parsers, hence it might be desirable to
+    ///get rid of them.
+    pub fn set_control_chars(&mut self, control_chars: ControlChars) {
+        self.options.control_chars = control_chars;
+    }
+
+    #[inline(always)]
+    ///Sets whether ANSI escape sequences, like colors or cursor movement, should be removed from written data.
+    ///
+    ///Useful when capturing output intended for terminal, as logcat displays such
+    ///sequences as garbage.
+    ///
+    ///Sequence may span across multiple writes.
+    pub fn set_strip_ansi(&mut self, strip_ansi: bool) {
+        self.options.strip_ansi = strip_ansi;
+        self.ansi = AnsiState::Text;
+    }
+
     #[inline(always)]
     ///Returns content of written buffer.
     pub fn buffer(&self) -> &[u8] {
@@ -157,31 +925,93 @@ impl Writer {
         }
     }
 
-    fn inner_flush(&mut self) {
-        unsafe {
-            (self.buffer.as_mut_ptr() as *mut u8).add(self.len).write(0);
-            __android_log_write(self.prio as _, self.tag.as_ptr() as _, self.buffer.as_ptr() as *const _);
-        }
-        self.len = 0;
+    #[doc(hidden)]
+    #[inline(always)]
+    //Makes writer to ignore minimum priority, used by `log_unchecked!`.
+    pub const fn __unchecked(mut self) -> Self {
+        self.options.unchecked = true;
+        self
     }
 
-    #[inline]
-    fn copy_data<'a>(&mut self, text: &'a [u8]) -> &'a [u8] {
-        let mut write_len = cmp::min(BUFFER_CAPACITY.saturating_sub(self.len), text.len());
+    fn inner_flush(&mut self) {
+        if self.options.unchecked || level::is_enabled(self.prio) {
+            #[cfg(feature = "std")]
+            mdc::append(self);
 
-        #[inline(always)]
-        fn is_char_boundary(text: &[u8], idx: usize) -> bool {
-            if idx == 0 {
-                return true;
+            unsafe {
+                (self.buffer.as_mut_ptr() as *mut u8).add(self.len).write(0);
             }
 
-            match text.get(idx) {
-                None => idx == text.len(),
-                Some(&byte) => (byte as i8) >= -0x40
+            #[cfg(feature = "std")]
+            let written = self.default_tag && scope::with_tag(|tag| self.write_log(tag.as_tag_ptr())).is_some();
+            #[cfg(feature = "ndk-context")]
+            let written = written || (self.default_tag && context::package_tag().map(|tag| self.write_log(tag.as_tag_ptr())).is_some());
+            #[cfg(not(feature = "std"))]
+            let written = false;
+
+            if !written {
+                self.write_log(self.tag.as_tag_ptr());
             }
         }
+        //Truncation might leave data past `len`
+        #[cfg(feature = "zeroize")]
+        self.wipe();
+        self.len = 0;
+        self.truncated = false;
+    }
 
-        #[inline(never)]
+    #[cfg(feature = "zeroize")]
+    #[inline(never)]
+    fn wipe(&mut self) {
+        let ptr = self.as_mut_ptr();
+        for idx in 0..=BUFFER_CAPACITY {
+            unsafe {
+                ptr::write_volatile(ptr.add(idx), 0);
+            }
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[inline(always)]
+    fn write_log(&self, tag: *const c_char) {
+        #[cfg(feature = "metrics")]
+        metrics::record(self.prio);
+
+        #[cfg(feature = "std")]
+        unsafe {
+            let prio = self.prio as i32;
+            let text = self.buffer.as_ptr() as *const c_char;
+            health::write_with_failover(prio, sys::tag_c_str(tag), CStr::from_ptr(text), || self.write_entry(tag, text));
+        }
+
+        #[cfg(not(feature = "std"))]
+        health::record(unsafe {
+            self.write_entry(tag, self.buffer.as_ptr() as *const _)
+        });
+    }
+
+    #[inline(always)]
+    unsafe fn write_entry(&self, tag: *const c_char, text: *const c_char) -> i32 {
+        let prio = self.prio as i32;
+
+        #[cfg(feature = "source-location")]
+        if let Some((file, line)) = self.location {
+            if let Some(result) = sys::log_message(self.log_id as i32, prio, tag, file, line, text) {
+                return result;
+            }
+        }
+
+        match self.log_id {
+            LogId::MAIN => __android_log_write(prio, tag, text),
+            log_id => sys::__android_log_buf_write(log_id as i32, prio, tag, text),
+        }
+    }
+
+    #[inline]
+    fn copy_data<'a>(&mut self, text: &'a [u8]) -> &'a [u8] {
+        let mut write_len = cmp::min(BUFFER_CAPACITY.saturating_sub(self.len), text.len());
+
+        #[inline(never)]
         #[cold]
         fn shift_by_char_boundary(text: &[u8], mut size: usize) -> usize {
             while !is_char_boundary(text, size) {
@@ -202,24 +1032,588 @@ impl Writer {
         &text[write_len..]
     }
 
+    #[inline]
+    ///Writes unsigned integer in decimal, without use of `core::fmt`.
+    pub fn append_uint(&mut self, value: u64) {
+        let mut buffer = [0u8; 20];
+        let len = format_uint(value, &mut buffer);
+        self.write_data(&buffer[..len]);
+    }
+
+    #[inline]
+    ///Writes signed integer in decimal, without use of `core::fmt`.
+    pub fn append_int(&mut self, value: i64) {
+        let mut buffer = [b'-'; 21];
+        let sign = (value < 0) as usize;
+        let len = format_uint(value.unsigned_abs(), &mut buffer[sign..]);
+        self.write_data(&buffer[..sign + len]);
+    }
+
+    ///Writes floating point number, without use of `core::fmt`.
+    ///
+    ///Fraction is rounded to 6 digits, omitting trailing zeros.
+    ///Numbers starting from `1e16` or below `1e-4` are written in scientific notation, like `1.5e-7`.
+    ///
+    ///Note that, unlike `core::fmt`, output is not guaranteed to be the shortest round-trip representation.
+    pub fn append_float(&mut self, value: f64) {
+        const FRACTION: u64 = 1_000_000;
+
+        if value.is_nan() {
+            return self.write_data(b"NaN");
+        }
+
+        let mut buffer = [b'-'; 32];
+        let mut len = value.is_sign_negative() as usize;
+        let mut value = value.abs();
+
+        if value.is_infinite() {
+            buffer[len..len + 3].copy_from_slice(b"inf");
+            return self.write_data(&buffer[..len + 3]);
+        }
+
+        let mut exp = 0i64;
+        if value >= 1e16 {
+            while value >= 10.0 {
+                value /= 10.0;
+                exp += 1;
+            }
+        } else if value != 0.0 && value < 1e-4 {
+            while value < 1.0 {
+                value *= 10.0;
+                exp -= 1;
+            }
+        }
+
+        let mut int = value as u64;
+        let mut fraction = ((value - int as f64) * FRACTION as f64 + 0.5) as u64;
+        if fraction >= FRACTION {
+            fraction -= FRACTION;
+            int += 1;
+        }
+        if exp != 0 && int >= 10 {
+            int /= 10;
+            exp += 1;
+        }
+
+        len += format_uint(int, &mut buffer[len..]);
+        if fraction != 0 {
+            buffer[len] = b'.';
+            len += 1;
+            let mut digits = FRACTION / 10;
+            while fraction != 0 {
+                buffer[len] = b'0' + (fraction / digits) as u8;
+                len += 1;
+                fraction %= digits;
+                digits /= 10;
+            }
+        }
+        if exp != 0 {
+            buffer[len] = b'e';
+            len += 1;
+            if exp < 0 {
+                buffer[len] = b'-';
+                len += 1;
+            }
+            len += format_uint(exp.unsigned_abs(), &mut buffer[len..]);
+        }
+
+        self.write_data(&buffer[..len]);
+    }
+
+    ///Writes supplied data, replacing invalid UTF-8 sequences with `U+FFFD`.
+    pub fn write_lossy(&mut self, data: &[u8]) {
+        for chunk in data.utf8_chunks() {
+            self.write_data(chunk.valid().as_bytes());
+            if !chunk.invalid().is_empty() {
+                self.write_data(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+    }
+
+    ///Writes UTF-16 text, replacing unpaired surrogates with `U+FFFD`.
+    ///
+    ///Text is converted to UTF-8 by small chunks, without intermediate `String`, which makes it
+    ///suitable for strings obtained via JNI's `GetStringChars`.
+    pub fn write_utf16(&mut self, text: &[u16]) {
+        let mut chunk = [0u8; 256];
+        let mut len = 0;
+
+        for ch in char::decode_utf16(text.iter().copied()) {
+            if len + 4 > chunk.len() {
+                self.write_data(&chunk[..len]);
+                len = 0;
+            }
+            len += ch.unwrap_or(char::REPLACEMENT_CHARACTER).encode_utf8(&mut chunk[len..]).len();
+        }
+        self.write_data(&chunk[..len]);
+    }
+
+    ///Writes "What a Terrible Failure" message as its own entry with FATAL priority.
+    ///
+    ///Buffered data, if any, is flushed beforehand, while priority of writer is unchanged.
+    ///Handler set via `set_wtf_handler` is called with message text before it is written.
+    ///Without `std` feature, handler receives only last entry of message, if it is split.
+    pub fn wtf(&mut self, args: fmt::Arguments<'_>) {
+        self.flush();
+
+        let prio = mem::replace(&mut self.prio, LogPriority::FATAL);
+        match wtf::handler() {
+            #[cfg(feature = "std")]
+            Some(handler) => {
+                //Handler receives whole message, without prefix of entry
+                let text = match args.as_str() {
+                    Some(text) => std::borrow::Cow::Borrowed(text),
+                    None => std::borrow::Cow::Owned(std::fmt::format(args)),
+                };
+                handler(&text);
+                let _ = fmt::Write::write_str(self, &text);
+            },
+            #[cfg(not(feature = "std"))]
+            Some(handler) => {
+                let _ = fmt::Write::write_fmt(self, args);
+                handler(core::str::from_utf8(self.buffer()).unwrap_or_default());
+            },
+            None => {
+                let _ = fmt::Write::write_fmt(self, args);
+            },
+        }
+        self.flush();
+        self.prio = prio;
+    }
+
+    ///Writes hex dump of `data`, with offset, hex and ASCII columns, 16 bytes per line.
+    ///
+    ///As many lines as possible are written per entry, without splitting lines across entries.
+    ///Buffered data, if any, is flushed beforehand.
+    ///
+    ///```text
+    ///00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 00 ff  |Hello, World!...|
+    ///```
+    pub fn write_hexdump(&mut self, data: &[u8]) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        self.flush();
+        for (idx, chunk) in data.chunks(16).enumerate() {
+            let mut line = [b' '; 78];
+            let offset = idx * 16;
+            for digit in 0..8 {
+                line[digit] = HEX[(offset >> ((7 - digit) * 4)) & 0xf];
+            }
+            for (pos, byte) in chunk.iter().enumerate() {
+                let hex = 10 + pos * 3 + pos / 8;
+                line[hex] = HEX[(byte >> 4) as usize];
+                line[hex + 1] = HEX[(byte & 0xf) as usize];
+                line[61 + pos] = if byte.is_ascii_graphic() || *byte == b' ' { *byte } else { b'.' };
+            }
+            line[60] = b'|';
+            line[61 + chunk.len()] = b'|';
+            let line = &line[..62 + chunk.len()];
+
+            if self.len > 0 {
+                if self.len + line.len() + 1 > BUFFER_CAPACITY {
+                    self.flush();
+                } else {
+                    self.write_data(b"\n");
+                }
+            }
+            self.write_data(line);
+        }
+        self.flush();
+    }
+
+    ///Writes every item as its own entry.
+    ///
+    ///Buffered data, if any, is flushed before writing items.
+    pub fn write_lines<I: IntoIterator>(&mut self, lines: I) where I::Item: AsRef<str> {
+        self.flush();
+        for line in lines {
+            self.write_data(line.as_ref().as_bytes());
+            self.flush();
+        }
+    }
+
     ///Writes supplied text to the buffer.
     ///
     ///On buffer overflow, data is logged via `__android_log_write`
     ///and buffer is filled with the rest of `data`
-    pub fn write_data(&mut self, mut data: &[u8]) {
+    pub fn write_data(&mut self, data: &[u8]) {
+        if self.options.normalize_newlines {
+            self.write_normalized(data);
+        } else {
+            self.write_text(data);
+        }
+
+        if self.len > self.options.flush_threshold as usize {
+            self.inner_flush();
+        }
+    }
+
+    fn write_normalized(&mut self, mut data: &[u8]) {
+        if self.after_cr && !data.is_empty() {
+            self.after_cr = false;
+            if data[0] == b'\n' {
+                data = &data[1..];
+            }
+        }
+
+        while let Some(idx) = data.iter().position(|byte| *byte == b'\r') {
+            self.write_text(&data[..idx]);
+            self.write_text(b"\n");
+            data = &data[idx + 1..];
+
+            match data.first() {
+                Some(b'\n') => data = &data[1..],
+                Some(_) => (),
+                None => self.after_cr = true,
+            }
+        }
+
+        self.write_text(data);
+    }
+
+    #[inline]
+    fn write_text(&mut self, data: &[u8]) {
+        if self.options.strip_ansi {
+            self.write_ansi_stripped(data);
+        } else {
+            self.write_sanitized(data);
+        }
+    }
+
+    fn write_ansi_stripped(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.ansi == AnsiState::Text {
+                match data.iter().position(|byte| *byte == 0x1b) {
+                    Some(idx) => {
+                        self.write_sanitized(&data[..idx]);
+                        self.ansi = AnsiState::Escape;
+                        data = &data[idx + 1..];
+                    },
+                    None => {
+                        self.write_sanitized(data);
+                        break;
+                    },
+                }
+            } else {
+                let mut idx = 0;
+                while idx < data.len() && self.ansi != AnsiState::Text {
+                    self.ansi = self.ansi.next(data[idx]);
+                    idx += 1;
+                }
+                data = &data[idx..];
+            }
+        }
+    }
+
+    #[inline]
+    fn write_sanitized(&mut self, data: &[u8]) {
+        match self.options.control_chars {
+            ControlChars::Keep => self.write_split(data),
+            control_chars => self.write_control_chars(data, control_chars),
+        }
+    }
+
+    fn write_control_chars(&mut self, mut data: &[u8], control_chars: ControlChars) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        while let Some(idx) = data.iter().position(|byte| is_control_char(*byte)) {
+            self.write_split(&data[..idx]);
+
+            if control_chars == ControlChars::Escape {
+                //Escape is never split between entries
+                if self.options.overflow == Overflow::Chunk && BUFFER_CAPACITY - self.len < 4 {
+                    report_truncation("message exceeds buffer capacity and is split");
+                    self.flush();
+                }
+                let byte = data[idx];
+                self.write_raw(&[b'\\', b'x', HEX[(byte >> 4) as usize], HEX[(byte & 0xf) as usize]]);
+            }
+
+            data = &data[idx + 1..];
+        }
+
+        self.write_split(data);
+    }
+
+    #[cfg(feature = "std")]
+    //Appends text to entry being flushed, sanitized the same way as message, as much as it fits.
+    //
+    //Newlines are written as spaces by line buffered writer, as entry cannot be split at this point.
+    fn append_sanitized(&mut self, data: &[u8]) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let mut ansi = AnsiState::Text;
+        let mut start = 0;
+        for (idx, byte) in data.iter().copied().enumerate() {
+            let is_ansi = self.options.strip_ansi && (ansi != AnsiState::Text || byte == 0x1b);
+            let is_control = !is_ansi && self.options.control_chars != ControlChars::Keep && is_control_char(byte);
+            let is_newline = self.options.line_buffered && byte == b'\n';
+            if !(is_ansi || is_control || is_newline) {
+                continue;
+            }
+
+            if !self.copy_data(&data[start..idx]).is_empty() {
+                return;
+            }
+            start = idx + 1;
+
+            let replacement: &[u8] = if is_ansi {
+                ansi = ansi.next(byte);
+                &[]
+            } else if is_newline {
+                b" "
+            } else if self.options.control_chars == ControlChars::Escape {
+                &[b'\\', b'x', HEX[(byte >> 4) as usize], HEX[(byte & 0xf) as usize]]
+            } else {
+                &[]
+            };
+            if BUFFER_CAPACITY - self.len < replacement.len() {
+                return;
+            }
+            self.copy_data(replacement);
+        }
+
+        self.copy_data(&data[start..]);
+    }
+
+    #[inline]
+    fn write_split(&mut self, mut data: &[u8]) {
+        if self.options.line_buffered {
+            while let Some(idx) = data.iter().position(|byte| *byte == b'\n') {
+                self.write_raw(&data[..idx]);
+                self.flush();
+                data = &data[idx + 1..];
+            }
+        }
+
+        self.write_raw(data);
+    }
+
+    #[inline]
+    //Writes prefix of new entry into empty buffer.
+    fn begin_entry(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            scope::write_correlation_id(self);
+            scope::write_indent(self);
+        }
+    }
+
+    fn write_raw(&mut self, mut data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        if self.options.overflow == Overflow::TruncateWithEllipsis {
+            self.write_truncated(data);
+            return;
+        }
+
         loop {
+            if self.len == 0 {
+                self.begin_entry();
+            }
             data = self.copy_data(data);
 
             if data.is_empty() {
                 break;
             } else {
+                report_truncation("message exceeds buffer capacity and is split");
                 self.flush();
             }
         }
     }
+
+    fn write_truncated(&mut self, data: &[u8]) {
+        const ELLIPSIS: &str = "…";
+
+        if self.truncated {
+            return;
+        }
+
+        if self.len == 0 {
+            self.begin_entry();
+        }
+
+        if self.copy_data(data).is_empty() {
+            return;
+        }
+        report_truncation("message exceeds buffer capacity and is truncated");
+
+        let mut len = cmp::min(self.len, BUFFER_CAPACITY - ELLIPSIS.len());
+        while !is_char_boundary(self.buffer(), len) {
+            len -= 1;
+        }
+
+        unsafe {
+            ptr::copy_nonoverlapping(ELLIPSIS.as_ptr(), self.as_mut_ptr().add(len), ELLIPSIS.len());
+        }
+        self.len = len + ELLIPSIS.len();
+        self.truncated = true;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: AsTag> Writer<T> {
+    #[inline]
+    ///Writes OS string, replacing invalid UTF-8 sequences with `U+FFFD`.
+    ///
+    ///Unlike `OsStr::to_string_lossy`, it never allocates.
+    pub fn write_os_str(&mut self, text: &std::ffi::OsStr) {
+        self.write_lossy(text.as_encoded_bytes());
+    }
+
+    #[inline]
+    ///Writes path, replacing invalid UTF-8 sequences with `U+FFFD`.
+    ///
+    ///Unlike `Path::to_string_lossy`, it never allocates.
+    pub fn write_path(&mut self, path: &std::path::Path) {
+        self.write_os_str(path.as_os_str());
+    }
+
+    #[cfg(feature = "serde")]
+    #[inline]
+    ///Writes `value` serialized as JSON.
+    ///
+    ///Serialization is performed directly into buffer, that is flushed whenever it is full.
+    ///Hence no intermediate `String` is allocated.
+    ///
+    ///```rust,no_run
+    ///use androidy_log::{LogPriority, Writer};
+    ///
+    ///let mut writer = Writer::new("MyTag", LogPriority::INFO);
+    ///writer.write_json(&serde_json::json!({"state": "ready"})).expect("to serialize");
+    ///```
+    pub fn write_json<S: serde::Serialize + ?Sized>(&mut self, value: &S) -> serde_json::Result<()> {
+        serde_json::to_writer(self, value)
+    }
+
+    //Returns number of bytes of `buf` that fit into buffer, flushing it if nothing fits.
+    fn reserve_partial_write(&mut self, buf: &[u8]) -> usize {
+        #[inline]
+        fn fit_len(buf: &[u8], available: usize) -> usize {
+            let mut size = cmp::min(available, buf.len());
+            while !is_char_boundary(buf, size) {
+                size -= 1;
+            }
+            size
+        }
+
+        let size = fit_len(buf, BUFFER_CAPACITY - self.len);
+        if size == 0 && !buf.is_empty() {
+            self.flush();
+            match fit_len(buf, BUFFER_CAPACITY) {
+                //Not a valid UTF-8, so just consume what fits
+                0 => cmp::min(BUFFER_CAPACITY, buf.len()),
+                size => size,
+            }
+        } else {
+            size
+        }
+    }
+}
+
+///Writes UTF-16 `text` as single message, replacing unpaired surrogates with `U+FFFD`.
+///
+///- `tag` - Log message tag, truncated to first 23 bytes.
+///- `prio` - Logging priority.
+pub fn log_utf16(tag: &str, prio: LogPriority, text: &[u16]) {
+    let mut writer = Writer::new(tag, prio);
+    writer.write_utf16(text);
+}
+
+///Writes `text` as single message directly, without buffering it in `Writer`.
+///
+///Intended for short messages, avoiding large stack frame and copy of `Writer`.
+///Unlike `Writer`, message is not sanitized and on Android it is truncated by liblog to about 1000 bytes.
+///
+///- `prio` - Logging priority. Message is discarded unless `is_loggable()`.
+///- `tag` - Log message tag, truncated to first 23 bytes.
+pub fn log_str(prio: LogPriority, tag: &str, text: &str) {
+    if !is_loggable(prio, tag) {
+        return;
+    }
+
+    let tag = TagHandle::new(tag);
+    unsafe {
+        sys::log_str(prio as i32, tag.as_tag_ptr(), text);
+    }
+}
+
+///Sets abort message, shown in tombstone when process aborts.
+///
+///Message is copied, truncated to first 4000 bytes without splitting UTF-8 characters and to first null character, if any.
+///Only first message is kept by Android, following ones are ignored.
+///Does nothing on other platforms.
+///
+///```rust,no_run
+///androidy_log::set_abort_message("Invalid state");
+///```
+pub fn set_abort_message(text: &str) {
+    let text = text.as_bytes();
+    let mut len = text.iter().position(|byte| *byte == 0).unwrap_or(text.len());
+    len = cmp::min(len, BUFFER_CAPACITY);
+    while !is_char_boundary(text, len) {
+        len -= 1;
+    }
+
+    let mut buffer = [0u8; BUFFER_CAPACITY + 1];
+    buffer[..len].copy_from_slice(&text[..len]);
+    //Buffer has at least one null character after message
+    sys::set_abort_message(unsafe { CStr::from_bytes_with_nul_unchecked(&buffer[..=len]) });
+}
+
+//Size of liblog's formatting buffer, excluding null character.
+const LOG_STR_MAX_LEN: usize = 1023;
+
+///Writes `text` as message, without need to manage `Writer`.
+///
+///Short messages are written directly via `log_str`, while longer ones are written via `Writer`, split into entries as needed.
+///
+///- `prio` - Logging priority. Message is discarded unless `is_loggable()`.
+///- `tag` - Log message tag, truncated to first 23 bytes.
+pub fn write(prio: LogPriority, tag: &str, text: &str) {
+    if text.len() <= LOG_STR_MAX_LEN {
+        log_str(prio, tag, text);
+    } else if is_loggable(prio, tag) {
+        let mut writer = Writer::new(tag, prio);
+        writer.write_data(text.as_bytes());
+    }
+}
+
+///Writes formatted message, without need to manage `Writer`.
+///
+///Message without format arguments is written via `write`, otherwise it is formatted into `Writer`.
+///
+///```rust,no_run
+///use androidy_log::LogPriority;
+///
+///androidy_log::write_fmt(LogPriority::INFO, "MyApp", format_args!("Value={}", 1));
+///```
+pub fn write_fmt(prio: LogPriority, tag: &str, args: fmt::Arguments<'_>) {
+    match args.as_str() {
+        Some(text) => write(prio, tag, text),
+        None => if is_loggable(prio, tag) {
+            let mut writer = Writer::new(tag, prio);
+            let _ = fmt::Write::write_fmt(&mut writer, args);
+        },
+    }
+}
+
+impl<T: AsTag + Clone> Clone for Writer<T> {
+    #[inline]
+    ///Creates copy of writer with the same tag, priority, settings and buffered data.
+    ///
+    ///Note that buffered data is written by each copy, so it might be desirable to flush writer before cloning it.
+    fn clone(&self) -> Self {
+        Self {
+            tag: self.tag.clone(),
+            ..*self
+        }
+    }
 }
 
-impl fmt::Write for Writer {
+impl<T: AsTag> fmt::Write for Writer<T> {
     #[inline]
     fn write_str(&mut self, text: &str) -> fmt::Result {
         self.write_data(text.as_bytes());
@@ -228,11 +1622,36 @@ impl fmt::Write for Writer {
     }
 }
 
+#[cfg(feature = "ufmt")]
+impl<T: AsTag> ufmt_write::uWrite for Writer<T> {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    ///Writes text, same as `fmt::Write`, allowing to use `ufmt::uwrite!` instead of `core::fmt`.
+    ///
+    ///```rust
+    ///use androidy_log::{LogPriority, Writer};
+    ///
+    ///let mut writer = Writer::new("MyTag", LogPriority::INFO);
+    ///let _ = ufmt::uwrite!(writer, "Value={}", 42);
+    ///```
+    fn write_str(&mut self, text: &str) -> Result<(), Self::Error> {
+        self.write_data(text.as_bytes());
+
+        Ok(())
+    }
+}
+
 #[cfg(feature = "std")]
-impl std::io::Write for Writer {
+impl<T: AsTag> std::io::Write for Writer<T> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.write_data(buf);
-        Ok(buf.len())
+        let size = match self.options.partial_writes {
+            true => self.reserve_partial_write(buf),
+            false => buf.len(),
+        };
+
+        self.write_data(&buf[..size]);
+        Ok(size)
     }
 
     #[inline(always)]
@@ -242,19 +1661,71 @@ impl std::io::Write for Writer {
     }
 }
 
-impl Drop for Writer {
+#[cfg(feature = "tokio")]
+///Writes are never pending, as data is only copied into buffer, while flush writes it immediately.
+impl<T: AsTag + Unpin> tokio::io::AsyncWrite for Writer<T> {
+    #[inline]
+    fn poll_write(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>, buf: &[u8]) -> core::task::Poll<std::io::Result<usize>> {
+        core::task::Poll::Ready(std::io::Write::write(self.get_mut(), buf))
+    }
+
+    #[inline]
+    fn poll_flush(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>) -> core::task::Poll<std::io::Result<()>> {
+        self.get_mut().flush();
+        core::task::Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_shutdown(self: core::pin::Pin<&mut Self>, ctx: &mut core::task::Context<'_>) -> core::task::Poll<std::io::Result<()>> {
+        self.poll_flush(ctx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+///Writes are never pending, as data is only copied into buffer, while flush writes it immediately.
+impl<T: AsTag + Unpin> futures_io::AsyncWrite for Writer<T> {
+    #[inline]
+    fn poll_write(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>, buf: &[u8]) -> core::task::Poll<std::io::Result<usize>> {
+        core::task::Poll::Ready(std::io::Write::write(self.get_mut(), buf))
+    }
+
+    #[inline]
+    fn poll_flush(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>) -> core::task::Poll<std::io::Result<()>> {
+        self.get_mut().flush();
+        core::task::Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: core::pin::Pin<&mut Self>, ctx: &mut core::task::Context<'_>) -> core::task::Poll<std::io::Result<()>> {
+        self.poll_flush(ctx)
+    }
+}
+
+impl<T: AsTag> Drop for Writer<T> {
     #[inline]
     fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        if self.len == 0 {
+            self.wipe();
+        }
         self.flush();
     }
 }
 
 #[macro_export]
 ///`println` alternative to write message with INFO priority.
+///
+///Tag can be specified as `tag: "MyTag"`, otherwise default tag is used.
 macro_rules! println {
     () => {{
         $crate::println!(" ");
     }};
+    (tag: $tag:expr, $($arg:tt)*) => {{
+        use core::fmt::Write;
+        let mut writer = $crate::Writer::new($tag, $crate::LogPriority::INFO);
+        let _ = write!(writer, $($arg)*);
+        drop(writer);
+    }};
     ($($arg:tt)*) => {{
         use core::fmt::Write;
         let mut writer = $crate::Writer::new_default($crate::LogPriority::INFO);
@@ -265,10 +1736,18 @@ macro_rules! println {
 
 #[macro_export]
 ///`eprintln` alternative to write message with ERROR priority.
+///
+///Tag can be specified as `tag: "MyTag"`, otherwise default tag is used.
 macro_rules! eprintln {
     () => {{
         $crate::println!(" ");
     }};
+    (tag: $tag:expr, $($arg:tt)*) => {{
+        use core::fmt::Write;
+        let mut writer = $crate::Writer::new($tag, $crate::LogPriority::ERROR);
+        let _ = write!(writer, $($arg)*);
+        drop(writer);
+    }};
     ($($arg:tt)*) => {{
         use core::fmt::Write;
         let mut writer = $crate::Writer::new_default($crate::LogPriority::ERROR);
@@ -277,13 +1756,756 @@ macro_rules! eprintln {
     }}
 }
 
+#[doc(hidden)]
+#[macro_export]
+///Resolves priority, allowing to specify `LogPriority` variant by name.
+macro_rules! __priority {
+    ($prio:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::LogPriority::*;
+        let prio: $crate::LogPriority = $prio;
+        prio
+    }}
+}
+
+#[macro_export]
+///Writes message with specified priority, bypassing any runtime checks.
+///
+///Priority can be either name of `LogPriority` variant or expression.
+///Message is written even if its priority is below `min_priority`, but on Android 11+ liblog still discards
+///messages below its own minimum priority, including one set via `set_min_priority`.
+///
+///Intended for messages that must always be logged, like crash breadcrumbs.
+///
+///```rust,no_run
+///let prio = androidy_log::LogPriority::WARN;
+///androidy_log::log_unchecked!(INFO, "Started");
+///androidy_log::log_unchecked!(prio, "Breadcrumb {}", 1);
+///```
+macro_rules! log_unchecked {
+    ($prio:expr, $($arg:tt)+) => {{
+        let mut writer = $crate::Writer::new_default($crate::__priority!($prio)).__unchecked();
+        let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+        drop(writer);
+    }}
+}
+
+#[macro_export]
+///Writes message with specified priority, if it is enabled.
+///
+///Priority can be either name of `LogPriority` variant or expression.
+///Message is not formatted, unless priority is enabled, see `min_priority` and `is_loggable`.
+///
+///Tag can be specified as `tag: "MyTag"`, otherwise default tag is used.
+///This applies to all logging macros.
+///
+///```rust,no_run
+///use androidy_log::LogPriority;
+///
+///let prio = if cfg!(debug_assertions) { LogPriority::DEBUG } else { LogPriority::INFO };
+///androidy_log::log!(prio, "Chosen at runtime");
+///androidy_log::log!(LogPriority::VERBOSE, "Value={}", 0);
+///androidy_log::log!(DEBUG, "Value={}", 1);
+///androidy_log::log!(DEBUG, tag: "Network", "Connected to {}", "localhost");
+///androidy_log::info!(tag: "Network", "Connected");
+///```
+macro_rules! log {
+    ($prio:expr, tag: $tag:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new(tag, prio);
+            $crate::__set_location!(writer);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }};
+    ($prio:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        if $crate::__is_default_loggable(prio) {
+            let mut writer = $crate::Writer::new_default(prio);
+            $crate::__set_location!(writer);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }}
+}
+
+#[cfg(feature = "source-location")]
+#[doc(hidden)]
+#[macro_export]
+///Sets location of macro's call site for writer.
+macro_rules! __set_location {
+    ($writer:ident) => {
+        $writer.set_location(file!(), line!())
+    }
+}
+
+#[cfg(not(feature = "source-location"))]
+#[doc(hidden)]
+#[macro_export]
+///Sets location of macro's call site for writer.
+macro_rules! __set_location {
+    ($writer:ident) => {
+    }
+}
+
+#[macro_export]
+///Writes message with specified priority and tag, if it is enabled.
+///
+///Unlike `log!`, it is written via `write_fmt`, bypassing `Writer` for short messages without format arguments.
+///
+///```rust,no_run
+///androidy_log::logf!(INFO, "MyApp", "Started");
+///androidy_log::logf!(INFO, "MyApp", "Value={}", 1);
+///```
+macro_rules! logf {
+    ($prio:expr, $tag:expr, $($arg:tt)+) => {{
+        $crate::write_fmt($crate::__priority!($prio), $tag, format_args!($($arg)+))
+    }}
+}
+
+#[cfg(feature = "serde")]
+#[macro_export]
+///Writes event's name, followed by value serialized as compact JSON, if priority is enabled.
+///
+///Requires `serde` feature.
+///
+///```rust,no_run
+///androidy_log::log_json!(INFO, "purchase", &serde_json::json!({"sku": "coins_100", "price": 99}));
+///androidy_log::log_json!(INFO, tag: "Analytics", "launch", &[1, 2, 3]);
+///```
+macro_rules! log_json {
+    ($prio:expr, tag: $tag:expr, $name:expr, $value:expr) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new(tag, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!("{} ", $name));
+            let _ = writer.write_json($value);
+            drop(writer);
+        }
+    }};
+    ($prio:expr, $name:expr, $value:expr) => {{
+        let prio = $crate::__priority!($prio);
+        if $crate::__is_default_loggable(prio) {
+            let mut writer = $crate::Writer::new_default(prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!("{} ", $name));
+            let _ = writer.write_json($value);
+            drop(writer);
+        }
+    }};
+}
+
+#[macro_export]
+///Writes every line of message as its own entry with specified priority, if it is enabled.
+///
+///Arguments are the same as of `log!`.
+///Lines are split on `\n`, which is not included in entries.
+///
+///```rust,no_run
+///androidy_log::log_lines!(INFO, "Usage:\n  --help\n  --version");
+///```
+macro_rules! log_lines {
+    ($prio:expr, tag: $tag:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new(tag, prio);
+            writer.set_line_buffered(true);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }};
+    ($prio:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        if $crate::__is_default_loggable(prio) {
+            let mut writer = $crate::Writer::new_default(prio);
+            writer.set_line_buffered(true);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }}
+}
+
+#[macro_export]
+///Writes message with VERBOSE priority, if it is enabled.
+///
+///Same as `verbose!`, named after `log` crate's level.
+macro_rules! trace {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::VERBOSE, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::VERBOSE, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with VERBOSE priority, if it is enabled.
+macro_rules! verbose {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::VERBOSE, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::VERBOSE, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with DEBUG priority, if it is enabled.
+macro_rules! debug {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::DEBUG, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::DEBUG, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with INFO priority, if it is enabled.
+macro_rules! info {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::INFO, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::INFO, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with WARN priority, if it is enabled.
+macro_rules! warn {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::WARN, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::WARN, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with ERROR priority, if it is enabled.
+macro_rules! error {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::ERROR, tag: $tag, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogPriority::ERROR, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Creates null terminated `TagBuf` at compile time.
+///
+///Compilation fails if tag exceeds 23 bytes or contains null character.
+///Resulting tag is always valid for `Writer::from_raw_parts`.
+///
+///```rust
+///use androidy_log::{LogPriority, TagBuf, Writer};
+///
+///const TAG: TagBuf = androidy_log::tag!("MyApp/Audio");
+///let writer = unsafe { Writer::from_raw_parts(TAG, LogPriority::INFO) };
+///```
+///
+///```rust,compile_fail
+///const TAG: androidy_log::TagBuf = androidy_log::tag!("MyApp/Audio/VeryLongName");
+///```
+///
+///```rust,compile_fail
+///const TAG: androidy_log::TagBuf = androidy_log::tag!("MyApp\0Audio");
+///```
+macro_rules! tag {
+    ($tag:expr) => {{
+        const TAG: $crate::TagBuf = $crate::__checked_tag($tag);
+        TAG
+    }};
+}
+
+#[macro_export]
+///Declares tag of current module, named after `module_path!()` and truncated to first 23 bytes.
+///
+///Tag is used by `module_log!` and its siblings, invoked within the same module.
+///
+///```rust,no_run
+///mod network {
+///    androidy_log::module_tag!();
+///
+///    pub fn connect() {
+///        androidy_log::module_info!("Connected"); //Logged with tag `rust_out::network`
+///    }
+///}
+///```
+macro_rules! module_tag {
+    () => {
+        #[allow(dead_code)]
+        const ANDROIDY_LOG_MODULE_TAG: $crate::TagHandle = $crate::TagHandle::new(module_path!());
+    };
+}
+
+#[macro_export]
+///Writes message with specified priority and tag declared by `module_tag!`, if priority is enabled.
+macro_rules! module_log {
+    ($prio:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        if $crate::is_loggable(prio, ANDROIDY_LOG_MODULE_TAG.as_str()) {
+            let mut writer = $crate::Writer::with_tag(&ANDROIDY_LOG_MODULE_TAG, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }}
+}
+
+#[macro_export]
+///Writes message with VERBOSE priority and tag declared by `module_tag!`, if it is enabled.
+macro_rules! module_verbose {
+    ($($arg:tt)+) => {
+        $crate::module_log!($crate::LogPriority::VERBOSE, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with DEBUG priority and tag declared by `module_tag!`, if it is enabled.
+macro_rules! module_debug {
+    ($($arg:tt)+) => {
+        $crate::module_log!($crate::LogPriority::DEBUG, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with INFO priority and tag declared by `module_tag!`, if it is enabled.
+macro_rules! module_info {
+    ($($arg:tt)+) => {
+        $crate::module_log!($crate::LogPriority::INFO, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with WARN priority and tag declared by `module_tag!`, if it is enabled.
+macro_rules! module_warn {
+    ($($arg:tt)+) => {
+        $crate::module_log!($crate::LogPriority::WARN, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with ERROR priority and tag declared by `module_tag!`, if it is enabled.
+macro_rules! module_error {
+    ($($arg:tt)+) => {
+        $crate::module_log!($crate::LogPriority::ERROR, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into radio buffer with specified priority, if it is enabled.
+///
+///Tag is `RADIO_TAG` unless specified.
+///With `slot:`, message is prefixed with SIM slot as `[slot] `, like in telephony framework logs.
+///
+///```rust,no_run
+///androidy_log::radio_log!(INFO, "Modem ready");
+///androidy_log::radio_log!(INFO, tag: "RILD", slot: 0, "Signal strength {}", -85);
+/////[0] Signal strength -85
+///```
+macro_rules! radio_log {
+    ($prio:expr, tag: $tag:expr, slot: $slot:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new_radio(tag, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!("[{}] ", $slot));
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }};
+    ($prio:expr, tag: $tag:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new_radio(tag, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }};
+    ($prio:expr, slot: $slot:expr, $($arg:tt)+) => {
+        $crate::radio_log!($prio, tag: $crate::RADIO_TAG, slot: $slot, $($arg)+)
+    };
+    ($prio:expr, $($arg:tt)+) => {
+        $crate::radio_log!($prio, tag: $crate::RADIO_TAG, $($arg)+)
+    };
+}
+
+#[macro_export]
+///Writes message into radio buffer with VERBOSE priority, if it is enabled.
+macro_rules! radio_verbose {
+    ($($arg:tt)+) => {
+        $crate::radio_log!($crate::LogPriority::VERBOSE, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into radio buffer with DEBUG priority, if it is enabled.
+macro_rules! radio_debug {
+    ($($arg:tt)+) => {
+        $crate::radio_log!($crate::LogPriority::DEBUG, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into radio buffer with INFO priority, if it is enabled.
+macro_rules! radio_info {
+    ($($arg:tt)+) => {
+        $crate::radio_log!($crate::LogPriority::INFO, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into radio buffer with WARN priority, if it is enabled.
+macro_rules! radio_warn {
+    ($($arg:tt)+) => {
+        $crate::radio_log!($crate::LogPriority::WARN, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into radio buffer with ERROR priority, if it is enabled.
+macro_rules! radio_error {
+    ($($arg:tt)+) => {
+        $crate::radio_log!($crate::LogPriority::ERROR, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into system buffer with specified priority and optional tag, if it is enabled.
+///
+///```rust,no_run
+///androidy_log::system_log!(INFO, tag: "vendor.foo-service", "Service started");
+///```
+macro_rules! system_log {
+    ($prio:expr, tag: $tag:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let mut writer = $crate::Writer::new_system(tag, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }};
+    ($prio:expr, $($arg:tt)+) => {{
+        let prio = $crate::__priority!($prio);
+        if $crate::__is_default_loggable(prio) {
+            let mut writer = $crate::Writer::new_default(prio);
+            writer.set_log_id($crate::LogId::SYSTEM);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!($($arg)+));
+            drop(writer);
+        }
+    }}
+}
+
+#[macro_export]
+///Writes message into system buffer with VERBOSE priority, if it is enabled.
+macro_rules! system_verbose {
+    ($($arg:tt)+) => {
+        $crate::system_log!($crate::LogPriority::VERBOSE, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into system buffer with DEBUG priority, if it is enabled.
+macro_rules! system_debug {
+    ($($arg:tt)+) => {
+        $crate::system_log!($crate::LogPriority::DEBUG, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into system buffer with INFO priority, if it is enabled.
+macro_rules! system_info {
+    ($($arg:tt)+) => {
+        $crate::system_log!($crate::LogPriority::INFO, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into system buffer with WARN priority, if it is enabled.
+macro_rules! system_warn {
+    ($($arg:tt)+) => {
+        $crate::system_log!($crate::LogPriority::WARN, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message into system buffer with ERROR priority, if it is enabled.
+macro_rules! system_error {
+    ($($arg:tt)+) => {
+        $crate::system_log!($crate::LogPriority::ERROR, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes message with FATAL priority, then aborts process.
+///
+///On Android message is also set as abort message, making it visible in tombstone.
+///Refer to `Writer::abort` for details.
+///
+///```rust,no_run
+///androidy_log::fatal!("Unrecoverable state {}", 1);
+///```
+macro_rules! fatal {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::Writer::new($tag, $crate::LogPriority::FATAL).abort(format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::abort(format_args!($($arg)+))
+    }
+}
+
+#[macro_export]
+///Aborts process with FATAL message if `cond` is true, like `LOG_ALWAYS_FATAL_IF` in C.
+///
+///Condition's text is used as message, unless message is specified.
+///Refer to `Writer::assert_failed` for details.
+///
+///```rust,no_run
+///let fd = -1;
+///androidy_log::fatal_if!(fd < 0);
+///androidy_log::fatal_if!(fd < 0, tag: "Io", "Invalid fd={}", fd);
+///```
+macro_rules! fatal_if {
+    ($cond:expr, tag: $tag:expr, $($arg:tt)+) => {
+        if $cond {
+            $crate::log_assert(stringify!($cond), $tag, format_args!($($arg)+))
+        }
+    };
+    ($cond:expr, tag: $tag:expr) => {
+        if $cond {
+            $crate::log_assert(stringify!($cond), $tag, format_args!(""))
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if $cond {
+            $crate::Writer::new_default($crate::LogPriority::FATAL).assert_failed(stringify!($cond), format_args!($($arg)+))
+        }
+    };
+    ($cond:expr) => {
+        if $cond {
+            $crate::Writer::new_default($crate::LogPriority::FATAL).assert_failed(stringify!($cond), format_args!(""))
+        }
+    };
+}
+
+#[macro_export]
+///Writes message with specified priority only if `cond` is true.
+///
+///Condition is evaluated first, and when it is false, neither `Writer` is created nor message is formatted.
+///Arguments after `cond` are the same as of `log!`.
+///
+///```rust,no_run
+///let verbose = false;
+///androidy_log::log_if!(verbose, DEBUG, "expensive: {:?}", [0u8; 64]);
+///```
+macro_rules! log_if {
+    ($cond:expr, $prio:expr, $($arg:tt)+) => {
+        if $cond {
+            $crate::log!($prio, $($arg)+)
+        }
+    }
+}
+
+#[macro_export]
+///Writes message with specified priority only first time call site is reached.
+///
+///Arguments are the same as of `log!`.
+///
+///```rust,no_run
+///for _ in 0..3 {
+///    androidy_log::log_once!(INFO, "Logged once");
+///}
+///```
+macro_rules! log_once {
+    ($prio:expr, $($arg:tt)+) => {{
+        static LOGGED: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+        if !LOGGED.swap(true, core::sync::atomic::Ordering::Relaxed) {
+            $crate::log!($prio, $($arg)+)
+        }
+    }}
+}
+
+#[macro_export]
+///Writes message with specified priority only every `n`-th time call site is reached, starting from first.
+///
+///Arguments after `n` are the same as of `log!`.
+///
+///```rust,no_run
+///for frame in 0..1000 {
+///    androidy_log::log_every_n!(100, INFO, "frame {}", frame);
+///}
+///```
+macro_rules! log_every_n {
+    ($n:expr, $prio:expr, $($arg:tt)+) => {{
+        static COUNTER: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+        let n: usize = $n;
+        if COUNTER.fetch_add(1, core::sync::atomic::Ordering::Relaxed) % n.max(1) == 0 {
+            $crate::log!($prio, $($arg)+)
+        }
+    }}
+}
+
+#[macro_export]
+///Writes message with WARN priority only first time call site is reached.
+///
+///Useful for deprecation warnings in hot paths.
+macro_rules! warn_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogPriority::WARN, $($arg)+)
+    }
+}
+
+#[macro_export]
+///Writes hex dump of bytes with specified tag and priority, if priority is enabled.
+///
+///Refer to `Writer::write_hexdump` for format.
+///
+///```rust,no_run
+///let packet = [0u8, 1, 2, 3];
+///androidy_log::hexdump!("Ble", DEBUG, &packet);
+///```
+macro_rules! hexdump {
+    ($tag:expr, $prio:expr, $data:expr) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            $crate::Writer::new(tag, prio).write_hexdump($data);
+        }
+    }}
+}
+
+#[macro_export]
+///`dbg` alternative, that writes `[file:line:column] expr = value` with DEBUG priority.
+///
+///Like `dbg`, it returns value of expression, or tuple of values if multiple expressions are passed.
+///Values are written using alternate `Debug` format.
+///
+///```rust,no_run
+///let ten = androidy_log::adbg!(5 * 2); //[src/main.rs:1:11] 5 * 2 = 10
+///let (one, two) = androidy_log::adbg!(1, 2);
+///androidy_log::adbg!(); //[src/main.rs:3:1]
+///```
+macro_rules! adbg {
+    () => {
+        $crate::log!($crate::LogPriority::DEBUG, "[{}:{}:{}]", file!(), line!(), column!())
+    };
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::log!($crate::LogPriority::DEBUG, "[{}:{}:{}] {} = {:#?}", file!(), line!(), column!(), stringify!($val), &tmp);
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::adbg!($val)),+,)
+    };
+}
+
+#[macro_export]
+///Writes message with ERROR priority and returns provided value from current function.
+///
+///```rust,no_run
+///fn parse(input: &str) -> Result<u32, core::num::ParseIntError> {
+///    match input.parse() {
+///        Ok(value) => Ok(value),
+///        Err(error) => androidy_log::bail_log!(Err(error), "Invalid input '{}'", input),
+///    }
+///}
+///
+///extern "C" fn ffi_shim(fd: i32) -> i32 {
+///    if fd < 0 {
+///        androidy_log::bail_log!(-1, "Invalid fd={}", fd);
+///    }
+///    0
+///}
+///```
+macro_rules! bail_log {
+    ($ret:expr, $($arg:tt)+) => {{
+        $crate::eprintln!($($arg)+);
+        return $ret;
+    }}
+}
+
+#[macro_export]
+///Asserts that condition is true, writing failure message with FATAL priority before panic.
+///
+///Message defaults to `assertion failed: <condition>`, same as `assert!`.
+///
+///```rust,no_run
+///let fd = 1;
+///androidy_log::assert_log!(fd >= 0);
+///androidy_log::assert_log!(tag: "MyApp", fd >= 0, "Invalid fd={}", fd);
+///```
+macro_rules! assert_log {
+    (tag: $tag:expr, $cond:expr $(,)?) => {
+        if !$cond {
+            $crate::__assert_failed(Some($tag), format_args!("assertion failed: {}", stringify!($cond)));
+        }
+    };
+    (tag: $tag:expr, $cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::__assert_failed(Some($tag), format_args!($($arg)+));
+        }
+    };
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::__assert_failed(None, format_args!("assertion failed: {}", stringify!($cond)));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::__assert_failed(None, format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+///Same as `assert_log!`, but only checked when `debug_assertions` are enabled.
+macro_rules! debug_assert_log {
+    ($($arg:tt)+) => {
+        if cfg!(debug_assertions) {
+            $crate::assert_log!($($arg)+);
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{LogPriority, Writer, TAG_MAX_LEN, DEFAULT_TAG};
+    use super::{ControlChars, Overflow, LogId, LogPriority, Writer, StaticWriter, TagHandle, TAG_MAX_LEN, DEFAULT_TAG};
     const TAG: &str = "Test";
     const TAG_OVERFLOW: &str = "123456789123456789123456789";
 
+    #[cfg(all(feature = "debug-truncation", debug_assertions))]
+    #[test]
+    fn should_panic_on_truncation() {
+        crate::sys::capture::STRICT.with(|strict| strict.set(true));
+        let split = std::panic::catch_unwind(|| Writer::new(TAG, LogPriority::WARN).write_data(&[b'1'; 4001]));
+        let truncated = std::panic::catch_unwind(|| {
+            let mut writer = Writer::new(TAG, LogPriority::WARN);
+            writer.set_overflow(Overflow::TruncateWithEllipsis);
+            writer.write_data(&[b'1'; 4001]);
+        });
+        let tag = std::panic::catch_unwind(|| Writer::new(TAG_OVERFLOW, LogPriority::WARN));
+        let fits = std::panic::catch_unwind(|| Writer::new(TAG, LogPriority::WARN).write_data(&[b'1'; 4000]));
+        crate::sys::capture::STRICT.with(|strict| strict.set(false));
+        crate::sys::capture::take();
+
+        assert!(split.is_err());
+        assert!(truncated.is_err());
+        assert!(tag.is_err());
+        assert!(fits.is_ok());
+    }
+
     #[test]
+    #[allow(clippy::sliced_string_as_bytes)]
     fn should_truncate_tag() {
         let writer = Writer::new(TAG_OVERFLOW, LogPriority::WARN);
         assert!(TAG_OVERFLOW.len() > TAG_MAX_LEN);
@@ -291,6 +2513,115 @@ mod tests {
         assert_eq!(tag, TAG_OVERFLOW[..TAG_MAX_LEN].as_bytes());
     }
 
+    #[test]
+    fn should_truncate_tag_on_char_boundary() {
+        let tag = "ΩΩΩΩΩΩΩΩΩΩΩΩ";
+        assert!(tag.len() > TAG_MAX_LEN);
+        let writer = Writer::new(tag, LogPriority::WARN);
+        let written = unsafe { core::slice::from_raw_parts(writer.tag.as_ptr() as *const u8, TAG_MAX_LEN + 1) };
+        assert_eq!(&written[..22], &tag.as_bytes()[..22]);
+        assert_eq!(&written[22..], &[0, 0]);
+    }
+
+    #[test]
+    fn should_map_verbosity_to_priority() {
+        assert_eq!(LogPriority::from_verbosity(0), LogPriority::WARN);
+        assert_eq!(LogPriority::from_verbosity(1), LogPriority::INFO);
+        assert_eq!(LogPriority::from_verbosity(2), LogPriority::DEBUG);
+        assert_eq!(LogPriority::from_verbosity(3), LogPriority::VERBOSE);
+        assert_eq!(LogPriority::from_verbosity(u8::MAX), LogPriority::VERBOSE);
+    }
+
+    #[test]
+    fn should_convert_log_id_name() {
+        for id in LogId::ALL.iter() {
+            assert_eq!(LogId::from_name(id.name()), Some(*id));
+        }
+
+        assert_eq!(LogId::from_name("crash"), Some(LogId::CRASH));
+        assert_eq!(LogId::from_name("radio"), Some(LogId::RADIO));
+        assert_eq!(LogId::from_name("Main"), None);
+    }
+
+    #[test]
+    fn should_write_into_log_buffer() {
+        let _ = crate::sys::capture::take_log_ids();
+
+        let mut writer = Writer::new_with_id("System", LogPriority::INFO, LogId::SYSTEM);
+        assert_eq!(writer.log_id(), LogId::SYSTEM);
+        writer.write_data(b"started");
+        writer.flush();
+        writer.set_log_id(LogId::MAIN);
+        writer.write_data(b"main");
+        drop(writer);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0], (LogPriority::INFO as i32, "System".into(), "started".into()));
+        assert_eq!(logs[1], (LogPriority::INFO as i32, "System".into(), "main".into()));
+        assert_eq!(crate::sys::capture::take_log_ids(), [LogId::SYSTEM as i32]);
+    }
+
+    #[cfg(feature = "source-location")]
+    #[test]
+    fn should_write_source_location() {
+        crate::sys::capture::LOCATIONS.with(|locations| locations.borrow_mut().clear());
+
+        let line = line!() + 1;
+        crate::info!("located");
+        let mut writer = Writer::new("Located", LogPriority::INFO);
+        writer.set_caller_location();
+        writer.write_data(b"caller");
+        drop(writer);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0].2, "located");
+        assert_eq!(logs[1].2, "caller");
+        let locations = crate::sys::capture::LOCATIONS.with(|locations| locations.take());
+        assert_eq!(locations, [(file!().into(), line), (file!().into(), line + 2)]);
+    }
+
+    #[test]
+    fn should_write_into_radio_buffer() {
+        let _ = crate::sys::capture::take_log_ids();
+
+        crate::radio_info!("Modem ready");
+        crate::radio_warn!(slot: 1, "No signal");
+        crate::radio_log!(DEBUG, tag: "RILD", slot: 0, "Strength {}", -85);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0], (LogPriority::INFO as i32, "RIL".into(), "Modem ready".into()));
+        assert_eq!(logs[1], (LogPriority::WARN as i32, "RIL".into(), "[1] No signal".into()));
+        assert_eq!(logs[2], (LogPriority::DEBUG as i32, "RILD".into(), "[0] Strength -85".into()));
+        assert_eq!(crate::sys::capture::take_log_ids(), [LogId::RADIO as i32; 3]);
+    }
+
+    #[test]
+    fn should_write_into_system_buffer() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        let _ = crate::sys::capture::take_log_ids();
+
+        crate::system_info!(tag: "FooService", "Started {}", 1);
+        crate::system_error!("Failed");
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0], (LogPriority::INFO as i32, "FooService".into(), "Started 1".into()));
+        assert_eq!(logs[1], (LogPriority::ERROR as i32, DEFAULT_TAG.into(), "Failed".into()));
+        assert_eq!(crate::sys::capture::take_log_ids(), [LogId::SYSTEM as i32; 2]);
+    }
+
+    #[test]
+    fn should_write_into_crash_buffer() {
+        let _ = crate::sys::capture::take_log_ids();
+
+        let mut writer = Writer::new_crash("Crash", LogPriority::FATAL);
+        writer.write_data(b"backtrace");
+        drop(writer);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0], (LogPriority::FATAL as i32, "Crash".into(), "backtrace".into()));
+        assert_eq!(crate::sys::capture::take_log_ids(), [LogId::CRASH as i32]);
+    }
+
     #[test]
     fn should_normal_write() {
         let mut writer = Writer::new_default(LogPriority::WARN);
@@ -312,6 +2643,486 @@ mod tests {
         assert_eq!(writer.buffer(), expected.as_bytes());
     }
 
+    #[test]
+    fn should_use_static_tag() {
+        const STATIC_TAG: &core::ffi::CStr = unsafe { core::ffi::CStr::from_bytes_with_nul_unchecked(b"Static\0") };
+
+        let mut writer = StaticWriter::new_static(STATIC_TAG, LogPriority::INFO);
+        assert!(core::mem::size_of::<StaticWriter>() < core::mem::size_of::<Writer>());
+        assert_eq!(writer.tag.to_bytes(), b"Static");
+
+        writer.write_data(b"data");
+        assert_eq!(writer.buffer(), b"data");
+    }
+
+    #[test]
+    fn should_share_tag_handle() {
+        const HANDLE: TagHandle = TagHandle::new("ΩΩΩΩΩΩΩΩΩΩΩΩ");
+        assert_eq!(HANDLE.as_c_str().to_bytes(), "ΩΩΩΩΩΩΩΩΩΩΩ".as_bytes());
+        assert_eq!(HANDLE.as_str(), "ΩΩΩΩΩΩΩΩΩΩΩ");
+        assert_eq!(TagHandle::new(TAG).as_c_str().to_bytes(), TAG.as_bytes());
+
+        let handle = TagHandle::new(TAG_OVERFLOW);
+        assert_eq!(handle.as_c_str().to_bytes(), &TAG_OVERFLOW.as_bytes()[..TAG_MAX_LEN]);
+
+        let mut first = handle.writer(LogPriority::INFO);
+        let mut second = Writer::with_tag(&handle, LogPriority::WARN);
+        first.write_data(b"first");
+        second.write_data(b"second");
+        drop(first);
+        drop(second);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 2);
+        assert!(logs.iter().all(|(_, tag, _)| tag.as_bytes() == &TAG_OVERFLOW.as_bytes()[..TAG_MAX_LEN]));
+    }
+
+    #[test]
+    fn should_sanitize_control_chars() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_data(b"a\x1b[0m\tb\nc\0\x7f");
+        assert_eq!(writer.buffer(), b"a\x1b[0m\tb\nc\0\x7f");
+        writer.len = 0;
+
+        writer.set_control_chars(ControlChars::Strip);
+        writer.write_data(b"a\x1b[0m\tb\nc\0\x7f");
+        assert_eq!(writer.buffer(), b"a[0m\tb\nc");
+        writer.len = 0;
+
+        writer.set_control_chars(ControlChars::Escape);
+        writer.write_data(b"a\x1b[0m\tb\nc\0\x7f");
+        assert_eq!(writer.buffer(), &b"a\\x1b[0m\tb\nc\\x00\\x7f"[..]);
+    }
+
+    #[test]
+    fn should_not_split_control_char_escape() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_control_chars(ControlChars::Escape);
+        writer.write_data(&[b'a'; 3998]);
+        writer.write_data(b"\x07b");
+        writer.flush();
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].2.len(), 3998);
+        assert_eq!(logs[1].2, "\\x07b");
+    }
+
+    #[test]
+    fn should_strip_ansi_sequences() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_strip_ansi(true);
+        writer.write_data(b"\x1b[1;31mred\x1b[0m \x1b]0;title\x07text\x1b(B\x1b]8;;link\x1b\\!");
+        assert_eq!(writer.buffer(), b"red text!");
+        writer.len = 0;
+
+        writer.write_data(b"a\x1b");
+        writer.write_data(b"[3");
+        writer.write_data(b"8;5;1mb\x1b[");
+        writer.write_data(b"Kc");
+        assert_eq!(writer.buffer(), b"abc");
+        writer.len = 0;
+
+        writer.set_control_chars(ControlChars::Escape);
+        writer.write_data(b"\x1b[0m\x08");
+        assert_eq!(writer.buffer(), b"\\x08");
+    }
+
+    #[test]
+    fn should_normalize_newlines() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_data(b"a\r\nb");
+        assert_eq!(writer.buffer(), b"a\r\nb");
+        writer.len = 0;
+
+        writer.set_normalize_newlines(true);
+        writer.write_data(b"a\r\nb\rc\r\r\nd\r");
+        writer.write_data(b"\ne\r");
+        writer.write_data(b"f");
+        assert_eq!(writer.buffer(), b"a\nb\nc\n\nd\ne\nf");
+        writer.len = 0;
+
+        writer.set_line_buffered(true);
+        writer.set_control_chars(ControlChars::Escape);
+        writer.write_data(b"first\r\nsecond\rthird");
+        assert_eq!(writer.buffer(), b"third");
+
+        let logs = crate::sys::capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["first", "second"]);
+    }
+
+    #[test]
+    fn should_write_lines_as_entries() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_data(b"pending");
+        writer.write_lines(["first", "", "second"].iter());
+        writer.write_lines(vec![String::from("third")]);
+        assert_eq!(writer.len, 0);
+
+        let logs = crate::sys::capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["pending", "first", "second", "third"]);
+    }
+
+    #[test]
+    fn should_clone_writer() {
+        let mut writer = Writer::new(TAG, LogPriority::ERROR);
+        writer.set_line_buffered(true);
+        writer.write_data(b"template");
+
+        let mut copy = writer.clone();
+        assert_eq!(copy.buffer(), b"template");
+        copy.write_data(b" copy\n");
+        writer.write_data(b" original\n");
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs, [(LogPriority::ERROR as i32, TAG.to_owned(), "template copy".to_owned()), (LogPriority::ERROR as i32, TAG.to_owned(), "template original".to_owned())]);
+    }
+
+    #[test]
+    fn should_write_lossy() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_lossy(b"valid \xF0\x90\x80 \xffend");
+        assert_eq!(writer.buffer(), "valid \u{FFFD} \u{FFFD}end".as_bytes());
+    }
+
+    #[test]
+    fn should_log_via_level_macros() {
+        crate::trace!("{}", 1);
+        crate::verbose!("2");
+        crate::debug!("3");
+        crate::info!("4");
+        crate::warn!("5");
+        crate::error!("6");
+        crate::log!(FATAL, "{}", 7);
+        crate::log!(INFO, "8");
+        for prio in [LogPriority::VERBOSE, LogPriority::ERROR] {
+            crate::log!(prio, "{}", 9);
+        }
+        crate::log!(LogPriority::DEBUG, "10");
+
+        let logs = crate::sys::capture::take();
+        let prios: std::vec::Vec<_> = logs.iter().map(|(prio, _, text)| (*prio, text.as_str())).collect();
+        assert_eq!(prios, [(2, "1"), (2, "2"), (3, "3"), (4, "4"), (5, "5"), (6, "6"), (7, "7"), (4, "8"), (2, "9"), (6, "9"), (3, "10")]);
+    }
+
+    #[test]
+    fn should_log_str_directly() {
+        crate::log_str(LogPriority::INFO, TAG_OVERFLOW, "direct\n");
+        crate::logf!(WARN, TAG, "static");
+        crate::logf!(WARN, TAG, "formatted {}", 1);
+
+        let logs = crate::sys::capture::take();
+        let texts: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(texts, [(4, &TAG_OVERFLOW[..TAG_MAX_LEN], "direct\n"), (5, TAG, "static"), (5, TAG, "formatted 1")]);
+    }
+
+    #[test]
+    fn should_set_abort_message() {
+        crate::set_abort_message("invalid\0state");
+        assert_eq!(crate::sys::capture::ABORT_MESSAGE.with(|message| message.borrow_mut().take()).as_deref(), Some("invalid"));
+
+        let long = "Ω".repeat(super::BUFFER_CAPACITY);
+        crate::set_abort_message(&long);
+        assert_eq!(crate::sys::capture::ABORT_MESSAGE.with(|message| message.borrow_mut().take()).as_deref(), Some(&long[..super::BUFFER_CAPACITY]));
+    }
+
+    #[test]
+    fn should_write_without_writer() {
+        let long = "a".repeat(super::BUFFER_CAPACITY + 1);
+        crate::write(LogPriority::INFO, TAG, "short");
+        crate::write(LogPriority::INFO, TAG, &long);
+        crate::write_fmt(LogPriority::WARN, TAG, format_args!("{}-{}", 1, 2));
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0], (LogPriority::INFO as i32, TAG.into(), "short".into()));
+        assert_eq!(logs[1].2.len(), super::BUFFER_CAPACITY);
+        assert_eq!(logs[2].2, "a");
+        assert_eq!(logs[3], (LogPriority::WARN as i32, TAG.into(), "1-2".into()));
+    }
+
+    #[test]
+    fn should_log_lines_as_entries() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        crate::log_lines!(INFO, "first\nsecond\n");
+        crate::log_lines!(WARN, tag: "Lines", "{}\n{}", 1, 2);
+
+        let logs = crate::sys::capture::take();
+        let texts: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(texts, [(4, DEFAULT_TAG, "first"), (4, DEFAULT_TAG, "second"), (5, "Lines", "1"), (5, "Lines", "2")]);
+    }
+
+    #[test]
+    fn should_log_if() {
+        struct Expensive<'a>(&'a core::cell::Cell<usize>);
+        impl core::fmt::Debug for Expensive<'_> {
+            fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                fmt.write_str("expensive")
+            }
+        }
+
+        let calls = core::cell::Cell::new(0);
+        crate::log_if!(false, DEBUG, "{:?}", Expensive(&calls));
+        crate::log_if!(calls.get() == 0, DEBUG, tag: "If", "{:?}", Expensive(&calls));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(crate::sys::capture::take(), [(LogPriority::DEBUG as i32, "If".into(), "expensive".into())]);
+    }
+
+    #[test]
+    fn should_log_once() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        for idx in 0..3 {
+            crate::log_once!(INFO, "once {}", idx);
+            crate::warn_once!(tag: "Deprecated", "warn {}", idx);
+        }
+
+        let logs = crate::sys::capture::take();
+        let logs: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(logs, [(4, "Rust", "once 0"), (5, "Deprecated", "warn 0")]);
+    }
+
+    #[test]
+    fn should_log_every_n() {
+        for idx in 0..7 {
+            crate::log_every_n!(3, DEBUG, "{}", idx);
+            crate::log_every_n!(0, INFO, tag: "Every", "{}", idx);
+        }
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.iter().filter(|(_, tag, _)| tag == "Every").count(), 7);
+        let logs: std::vec::Vec<_> = logs.iter().filter(|(prio, _, _)| *prio == LogPriority::DEBUG as i32).map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(logs, ["0", "3", "6"]);
+    }
+
+    #[test]
+    fn should_log_debug_expressions() {
+        let value = std::string::String::from("moved");
+        assert_eq!(crate::adbg!(5 * 2), 10);
+        assert_eq!(crate::adbg!(1, value), (1, std::string::String::from("moved")));
+        crate::adbg!();
+
+        let logs = crate::sys::capture::take();
+        let line = line!();
+        assert_eq!(logs.len(), 4);
+        assert!(logs.iter().all(|(prio, _, _)| *prio == LogPriority::DEBUG as i32));
+        assert_eq!(logs[0].2, std::format!("[{}:{}:20] 5 * 2 = 10", file!(), line - 5));
+        assert_eq!(logs[1].2, std::format!("[{}:{}:20] 1 = 1", file!(), line - 4));
+        assert_eq!(logs[2].2, std::format!("[{}:{}:20] value = \"moved\"", file!(), line - 4));
+        assert_eq!(logs[3].2, std::format!("[{}:{}:9]", file!(), line - 3));
+    }
+
+    #[test]
+    fn should_log_with_custom_tag() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        let tag = "Network";
+        crate::info!(tag: tag, "connected to {}", "localhost");
+        crate::log!(WARN, tag: "Audio", "{}", 2);
+        crate::println!(tag: "Print", "3");
+        crate::eprintln!(tag: "Print", "4");
+        crate::log!(ERROR, "tag: {}", 5);
+
+        let logs = crate::sys::capture::take();
+        let tags: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(tags, [(4, "Network", "connected to localhost"), (5, "Audio", "2"), (4, "Print", "3"), (6, "Print", "4"), (6, "Rust", "tag: 5")]);
+    }
+
+    #[test]
+    fn should_log_assertion_failure() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        let value = 1;
+        crate::assert_log!(value == 1);
+        crate::debug_assert_log!(tag: "Assert", value > 0, "unused");
+        let default = std::panic::catch_unwind(|| crate::assert_log!(value == 2));
+        let custom = std::panic::catch_unwind(|| crate::debug_assert_log!(tag: "Assert", value > 1, "value={}", value));
+
+        let logs = crate::sys::capture::take();
+        assert!(default.is_err());
+        assert_eq!(custom.is_err(), cfg!(debug_assertions));
+        assert_eq!(logs[0], (LogPriority::FATAL as i32, DEFAULT_TAG.into(), "assertion failed: value == 2".into()));
+        if cfg!(debug_assertions) {
+            assert_eq!(logs[1], (LogPriority::FATAL as i32, "Assert".into(), "value=1".into()));
+        }
+    }
+
+    #[test]
+    fn should_create_tag_at_compile_time() {
+        const AUDIO: crate::TagBuf = crate::tag!("MyApp/Audio");
+        let full = crate::tag!("12345678912345678912345");
+
+        let mut writer = unsafe { Writer::from_raw_parts(AUDIO, LogPriority::INFO) };
+        writer.write_data(b"1");
+        writer.flush();
+        let mut writer = unsafe { Writer::from_raw_parts(full, LogPriority::INFO) };
+        writer.write_data(b"2");
+        writer.flush();
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0].1, "MyApp/Audio");
+        assert_eq!(logs[1].1, "12345678912345678912345");
+    }
+
+    #[test]
+    fn should_log_with_module_tag() {
+        mod very_long_module_name {
+            crate::module_tag!();
+
+            pub fn log() {
+                crate::module_info!("{}", 1);
+                crate::module_log!(WARN, "2");
+            }
+        }
+
+        very_long_module_name::log();
+        let logs = crate::sys::capture::take();
+        let tags: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(tags, [(4, "androidy_log::tests::ve", "1"), (5, "androidy_log::tests::ve", "2")]);
+    }
+
+    #[cfg(all(feature = "tokio", feature = "futures-io"))]
+    #[test]
+    fn should_write_async() {
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+
+        let mut ctx = Context::from_waker(Waker::noop());
+        let mut writer = Writer::new(TAG, LogPriority::INFO);
+        writer.set_line_buffered(true);
+        assert!(matches!(tokio::io::AsyncWrite::poll_write(Pin::new(&mut writer), &mut ctx, b"tokio\nfutures"), Poll::Ready(Ok(13))));
+        assert!(matches!(futures_io::AsyncWrite::poll_write(Pin::new(&mut writer), &mut ctx, b"-io"), Poll::Ready(Ok(3))));
+        assert!(matches!(futures_io::AsyncWrite::poll_flush(Pin::new(&mut writer), &mut ctx), Poll::Ready(Ok(()))));
+        assert!(matches!(tokio::io::AsyncWrite::poll_write(Pin::new(&mut writer), &mut ctx, b"shutdown"), Poll::Ready(Ok(8))));
+        assert!(matches!(tokio::io::AsyncWrite::poll_shutdown(Pin::new(&mut writer), &mut ctx), Poll::Ready(Ok(()))));
+
+        let logs = crate::sys::capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["tokio", "futures-io", "shutdown"]);
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn should_write_via_ufmt() {
+        let mut writer = Writer::new(TAG, LogPriority::INFO);
+        ufmt::uwrite!(writer, "Value={} ", 42).unwrap();
+        ufmt::uwriteln!(writer, "{}", "text").unwrap();
+        assert_eq!(writer.buffer(), b"Value=42 text\n");
+    }
+
+    #[test]
+    fn should_write_hexdump() {
+        let data: std::vec::Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        crate::hexdump!(TAG, DEBUG, b"Hello, World!\n\0\xff ");
+        crate::hexdump!(TAG, DEBUG, &data);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs[0].2, "00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 00 ff  |Hello, World!...|\n00000010  20                                                | |");
+        assert_eq!(logs.len(), 3);
+        let lines: std::vec::Vec<_> = logs[1..].iter().flat_map(|log| log.2.split('\n')).collect();
+        assert_eq!(lines.len(), 63);
+        assert!(logs[1].2.len() <= 4000);
+        assert_eq!(lines[4], "00000040  40 41 42 43 44 45 46 47  48 49 4a 4b 4c 4d 4e 4f  |@ABCDEFGHIJKLMNO|");
+        assert_eq!(lines[62], "000003e0  e0 e1 e2 e3 e4 e5 e6 e7                           |........|");
+    }
+
+    #[test]
+    fn should_write_null_tag() {
+        let mut writer = Writer::with_tag(super::NullTag, LogPriority::INFO);
+        writer.write_data(b"untagged");
+        writer.flush();
+        assert_eq!(crate::sys::capture::take(), [(LogPriority::INFO as i32, "".into(), "untagged".into())]);
+    }
+
+    #[test]
+    fn should_write_utf16() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        let text: std::vec::Vec<u16> = "ロリ 😀".encode_utf16().collect();
+        writer.write_utf16(&text);
+        writer.write_utf16(&[0xD800, b'!' as u16]);
+        assert_eq!(writer.buffer(), "ロリ 😀\u{FFFD}!".as_bytes());
+        writer.len = 0;
+
+        let text: std::vec::Vec<u16> = "ロ".repeat(2000).encode_utf16().collect();
+        super::log_utf16(TAG, LogPriority::INFO, &text);
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].2.len(), 3999);
+        assert_eq!(logs[0].2.clone() + &logs[1].2, "ロ".repeat(2000));
+    }
+
+    #[test]
+    fn should_append_numbers() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        let mut check = |append: &dyn Fn(&mut Writer), expected: &str| {
+            append(&mut writer);
+            assert_eq!(writer.buffer(), expected.as_bytes());
+            writer.len = 0;
+        };
+
+        check(&|writer| writer.append_uint(0), "0");
+        check(&|writer| writer.append_uint(u64::MAX), "18446744073709551615");
+        check(&|writer| writer.append_int(-42), "-42");
+        check(&|writer| writer.append_int(i64::MIN), "-9223372036854775808");
+        check(&|writer| writer.append_float(0.0), "0");
+        check(&|writer| writer.append_float(-0.0), "-0");
+        check(&|writer| writer.append_float(0.1), "0.1");
+        check(&|writer| writer.append_float(-123.456), "-123.456");
+        check(&|writer| writer.append_float(1.000005), "1.000005");
+        check(&|writer| writer.append_float(9.9999999), "10");
+        check(&|writer| writer.append_float(1e20), "1e20");
+        check(&|writer| writer.append_float(2.5e-7), "2.5e-7");
+        check(&|writer| writer.append_float(f64::NAN), "NaN");
+        check(&|writer| writer.append_float(f64::NEG_INFINITY), "-inf");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_write_path() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_path(std::path::Path::new("/data/ロリ"));
+        writer.write_data(b" ");
+        writer.write_os_str(std::ffi::OsStr::new("file.txt"));
+        assert_eq!(writer.buffer(), "/data/ロリ file.txt".as_bytes());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            writer.len = 0;
+            writer.write_os_str(std::ffi::OsStr::from_bytes(b"bad\xff"));
+            assert_eq!(writer.buffer(), "bad\u{FFFD}".as_bytes());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_write_json() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_data(b"state=");
+        writer.write_json(&serde_json::json!({"name": "ロリ", "ids": [1, 2]})).unwrap();
+        assert_eq!(writer.buffer(), "state={\"ids\":[1,2],\"name\":\"ロリ\"}".as_bytes());
+        writer.len = 0;
+
+        let large = std::vec![u32::MAX; 1000];
+        writer.write_json(&large).unwrap();
+        writer.flush();
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs.iter().map(|log| log.2.as_str()).collect::<std::string::String>(), serde_json::to_string(&large).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_log_json_event() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        crate::log_json!(INFO, "purchase", &serde_json::json!({"sku": "coins", "price": 99}));
+        crate::log_json!(WARN, tag: "Analytics", "launch", &[1, 2]);
+
+        let logs = crate::sys::capture::take();
+        let texts: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(texts, [(4, DEFAULT_TAG, "purchase {\"price\":99,\"sku\":\"coins\"}"), (5, "Analytics", "launch [1,2]")]);
+    }
+
     #[test]
     fn should_handle_write_overflow() {
         let mut writer = Writer::new(TAG, LogPriority::WARN);
@@ -328,6 +3139,111 @@ mod tests {
         assert_eq!(writer.len, 23);
     }
 
+    #[test]
+    fn should_write_full_buffer_on_overflow() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        let data = TAG_OVERFLOW.as_bytes();
+        for _ in 0..=148 {
+            writer.write_data(data);
+        }
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, LogPriority::WARN as i32);
+        assert_eq!(logs[0].1, TAG);
+        assert_eq!(logs[0].2.len(), 4000);
+    }
+
+    #[test]
+    fn should_flush_on_threshold() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_flush_threshold(10);
+
+        writer.write_data(b"1234567891");
+        assert_eq!(writer.len, 10);
+        assert!(crate::sys::capture::take().is_empty());
+
+        writer.write_data(b"1");
+        assert_eq!(writer.len, 0);
+
+        writer.write_data(&[b'1'; 4010]);
+        assert_eq!(writer.len, 10);
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].2, "12345678911");
+        assert_eq!(logs[1].2.len(), 4000);
+    }
+
+    #[test]
+    fn should_truncate_with_ellipsis_on_overflow() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_overflow(Overflow::TruncateWithEllipsis);
+        let data = b"1234567891";
+
+        for _ in 0..399 {
+            writer.write_data(data);
+        }
+        assert_eq!(3990, writer.len);
+
+        writer.write_data("12345678ロリ".as_bytes());
+        assert_eq!(4000, writer.len);
+        assert!(writer.buffer().ends_with("911234567…".as_bytes()));
+
+        writer.write_data(data);
+        assert_eq!(4000, writer.len);
+
+        writer.flush();
+        writer.write_data(data);
+        assert_eq!(writer.buffer(), data);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_write_partially() {
+        use std::io::Write;
+
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_partial_writes(true);
+        let data = [b'1'; 3998];
+
+        assert_eq!(writer.write(&data).unwrap(), data.len());
+        //Nothing fits, so buffer is flushed
+        assert_eq!(writer.write("ロリ".as_bytes()).unwrap(), 6);
+        assert_eq!(writer.buffer(), "ロリ".as_bytes());
+
+        assert_eq!(writer.write(&[b'2'; 4000]).unwrap(), 3994);
+        assert_eq!(writer.write(b"3").unwrap(), 1);
+        assert_eq!(writer.buffer(), b"3");
+
+        let logs = crate::sys::capture::take();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].2.len(), 3998);
+        assert_eq!(logs[1].2.len(), 4000);
+
+        writer.write_all(&[b'4'; 8001]).unwrap();
+        assert_eq!(writer.len, 2);
+        assert_eq!(crate::sys::capture::take().len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn should_flush_io_write_per_line() {
+        use std::io::Write;
+
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_line_buffered(true);
+
+        writeln!(writer, "first").unwrap();
+        write!(writer, "second ").unwrap();
+        write!(writer, "line\nthird\n\nlast").unwrap();
+        assert_eq!(writer.buffer(), b"last");
+
+        let logs = crate::sys::capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["first", "second line", "third"]);
+    }
+
     #[test]
     fn should_handle_write_overflow_outside_of_char_boundary() {
         let mut writer = Writer::new(TAG, LogPriority::WARN);
@@ -349,4 +3265,35 @@ mod tests {
         assert_eq!(writer.buffer(), unicode.as_bytes());
     }
 
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn should_wipe_buffer_after_flush() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.write_data(b"secret");
+        writer.flush();
+
+        let raw = unsafe {
+            core::slice::from_raw_parts(writer.buffer.as_ptr() as *const u8, 7)
+        };
+        assert_eq!(raw, [0; 7]);
+        assert_eq!(crate::sys::capture::take()[0].2, "secret");
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn should_wipe_truncated_buffer_after_flush() {
+        let mut writer = Writer::new(TAG, LogPriority::WARN);
+        writer.set_overflow(Overflow::TruncateWithEllipsis);
+        writer.write_data(&[b'a'; 3996]);
+        writer.write_data("😀😀".as_bytes());
+        assert_eq!(writer.len, 3999);
+        writer.flush();
+
+        let raw = unsafe {
+            core::slice::from_raw_parts(writer.buffer.as_ptr() as *const u8, super::BUFFER_CAPACITY + 1)
+        };
+        assert!(raw.iter().all(|byte| *byte == 0));
+        assert!(crate::sys::capture::take()[0].2.ends_with('…'));
+    }
+
 }
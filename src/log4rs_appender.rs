@@ -0,0 +1,107 @@
+use crate::LogcatLogger;
+
+use std::boxed::Box;
+use std::string::String;
+
+use log4rs::append::Append;
+use log4rs::config::{Deserialize, Deserializers};
+
+#[derive(Debug)]
+///`log4rs` appender, that writes records via `LogcatLogger`.
+///
+///```rust,no_run
+///use androidy_log::{LogcatAppender, LogcatLogger};
+///use log4rs::config::{Appender, Config, Root};
+///
+///let config = Config::builder()
+///    .appender(Appender::builder().build("android", Box::new(LogcatAppender::new(LogcatLogger::with_tag("MyApp")))))
+///    .build(Root::builder().appender("android").build(log::LevelFilter::Info))
+///    .expect("valid config");
+///log4rs::init_config(config).expect("to init log4rs");
+///```
+pub struct LogcatAppender {
+    logger: LogcatLogger,
+}
+
+impl LogcatAppender {
+    #[inline(always)]
+    ///Creates appender, that writes records via `logger`.
+    pub const fn new(logger: LogcatLogger) -> Self {
+        Self {
+            logger,
+        }
+    }
+}
+
+impl Append for LogcatAppender {
+    #[inline]
+    fn append(&self, record: &log::Record<'_>) -> anyhow::Result<()> {
+        log::Log::log(&self.logger, record);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn flush(&self) {
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+///Configuration of `LogcatAppender`.
+pub struct LogcatAppenderConfig {
+    tag: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+///Deserializer of `LogcatAppender`, to be registered for config files.
+///
+///Without `tag` record's target is used as tag.
+///
+///```yaml
+///appenders:
+///  android:
+///    kind: android
+///    tag: MyApp
+///```
+///
+///```rust,no_run
+///let mut deserializers = log4rs::config::Deserializers::default();
+///deserializers.insert("android", androidy_log::LogcatAppenderDeserializer);
+///log4rs::init_file("log4rs.yaml", deserializers).expect("to init log4rs");
+///```
+pub struct LogcatAppenderDeserializer;
+
+impl Deserialize for LogcatAppenderDeserializer {
+    type Trait = dyn Append;
+    type Config = LogcatAppenderConfig;
+
+    fn deserialize(&self, config: Self::Config, _: &Deserializers) -> anyhow::Result<Box<Self::Trait>> {
+        let logger = match config.tag {
+            Some(tag) => LogcatLogger::with_tag(&tag),
+            None => LogcatLogger::new(),
+        };
+        Ok(Box::new(LogcatAppender::new(logger)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogcatAppenderConfig, LogcatAppenderDeserializer};
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    use log4rs::config::{Deserialize, Deserializers};
+
+    #[test]
+    fn should_append_records() {
+        let tagged = LogcatAppenderDeserializer.deserialize(LogcatAppenderConfig { tag: Some("Log4rs".into()) }, &Deserializers::new()).unwrap();
+        let untagged = LogcatAppenderDeserializer.deserialize(LogcatAppenderConfig { tag: None }, &Deserializers::new()).unwrap();
+
+        let args = format_args!("started {}", 1);
+        tagged.append(&log::Record::builder().target("Audio").level(log::Level::Warn).args(args).build()).unwrap();
+        let args = format_args!("connected");
+        untagged.append(&log::Record::builder().target("Net").level(log::Level::Debug).args(args).build()).unwrap();
+
+        assert_eq!(capture::take(), [(LogPriority::WARN as i32, "Log4rs".into(), "started 1".into()), (LogPriority::DEBUG as i32, "Net".into(), "connected".into())]);
+    }
+}
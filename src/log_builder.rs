@@ -0,0 +1,234 @@
+use crate::{LogcatLogger, LogPriority, TargetTag};
+use crate::logger::{level_priority, write_record};
+
+use core::fmt;
+use std::boxed::Box;
+use std::string::String;
+use std::vec::Vec;
+
+type Format = dyn Fn(&mut dyn fmt::Write, &log::Record<'_>) -> fmt::Result + Send + Sync;
+
+//Level of targets starting with `target`, or of all targets if it is `None`.
+struct Directive {
+    target: Option<String>,
+    level: log::LevelFilter,
+}
+
+//Parses `RUST_LOG` like filter, ignoring invalid directives.
+fn parse_filter(filter: &str, directives: &mut Vec<Directive>) {
+    for directive in filter.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+        let mut parts = directive.splitn(2, '=');
+        let (target, level) = match (parts.next(), parts.next()) {
+            (Some(target), Some(level)) => match level.trim().parse() {
+                Ok(level) => (Some(target.trim()), level),
+                Err(_) => continue,
+            },
+            (Some(part), None) => match part.parse() {
+                Ok(level) => (None, level),
+                Err(_) => (Some(part), log::LevelFilter::Trace),
+            },
+            _ => continue,
+        };
+
+        directives.push(Directive {
+            target: target.map(String::from),
+            level,
+        });
+    }
+}
+
+///Builder of `log` backend with `RUST_LOG` like filtering and custom formatting.
+///
+///```rust,no_run
+///use androidy_log::LogPriority;
+///
+///androidy_log::builder().tag("MyApp")
+///                       .min_level(LogPriority::DEBUG)
+///                       .filter("hyper=warn,my_app=trace")
+///                       .format(|writer, record| write!(writer, "[{}] {}", record.target(), record.args()))
+///                       .init()
+///                       .expect("to init logger");
+///```
+pub struct Builder {
+    logger: LogcatLogger,
+    min_level: LogPriority,
+    directives: Vec<Directive>,
+    format: Option<Box<Format>>,
+}
+
+///Creates `Builder` of `log` backend.
+pub fn builder() -> Builder {
+    Builder {
+        logger: LogcatLogger::new(),
+        min_level: LogPriority::VERBOSE,
+        directives: Vec::new(),
+        format: None,
+    }
+}
+
+impl Builder {
+    ///Uses `tag` for all records, truncated to first 23 bytes.
+    ///
+    ///By default record's target is used as tag.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.logger = LogcatLogger::with_tag(tag);
+        self
+    }
+
+    ///Sets policy to derive tag from record's target, unless tag is specified.
+    pub fn target_tag(mut self, target: TargetTag) -> Self {
+        self.logger = self.logger.with_target_tag(target);
+        self
+    }
+
+    ///Sets minimum priority of records, regardless of filter.
+    pub fn min_level(mut self, prio: LogPriority) -> Self {
+        self.min_level = prio;
+        self
+    }
+
+    ///Adds filter directives in format of `RUST_LOG`, like `warn,my_app=trace,my_app::net=off`.
+    ///
+    ///Directive `target=level` applies to all targets starting with `target`, with the longest one
+    ///taking precedence, while `level` applies to all other targets.
+    ///By default all targets are enabled.
+    pub fn filter(mut self, filter: &str) -> Self {
+        parse_filter(filter, &mut self.directives);
+        self
+    }
+
+    ///Sets function to format records, instead of writing message only.
+    pub fn format<F: Fn(&mut dyn fmt::Write, &log::Record<'_>) -> fmt::Result + Send + Sync + 'static>(mut self, format: F) -> Self {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    ///Builds logger without installing it.
+    pub fn build(self) -> FilteredLogger {
+        let mut directives = self.directives;
+        //Later directives override earlier ones of the same target.
+        directives.reverse();
+        directives.sort_by_key(|directive| core::cmp::Reverse(directive.target.as_ref().map_or(0, |target| target.len() + 1)));
+        let default = directives.iter().find(|directive| directive.target.is_none()).map_or(log::LevelFilter::Trace, |directive| directive.level);
+
+        FilteredLogger {
+            logger: self.logger,
+            min_level: self.min_level,
+            directives,
+            default,
+            format: self.format,
+        }
+    }
+
+    ///Builds logger and installs it as global logger of `log` facade.
+    ///
+    ///Fails if global logger is already installed.
+    pub fn init(self) -> Result<(), log::SetLoggerError> {
+        let logger = Box::leak(Box::new(self.build()));
+        log::set_logger(logger)?;
+        log::set_max_level(logger.max_level());
+        Ok(())
+    }
+}
+
+///`log` backend created by `Builder`.
+pub struct FilteredLogger {
+    logger: LogcatLogger,
+    min_level: LogPriority,
+    //Sorted by length of target in descending order.
+    directives: Vec<Directive>,
+    default: log::LevelFilter,
+    format: Option<Box<Format>>,
+}
+
+impl FilteredLogger {
+    ///Returns the most verbose level, that can be enabled.
+    pub fn max_level(&self) -> log::LevelFilter {
+        self.directives.iter().map(|directive| directive.level).chain(Some(self.default)).max().unwrap_or(self.default)
+    }
+
+    fn level(&self, target: &str) -> log::LevelFilter {
+        for directive in self.directives.iter() {
+            match &directive.target {
+                Some(name) if target.starts_with(name.as_str()) => return directive.level,
+                Some(_) => continue,
+                None => break,
+            }
+        }
+        self.default
+    }
+}
+
+impl log::Log for FilteredLogger {
+    #[inline]
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        level_priority(metadata.level()) as i32 >= self.min_level as i32
+        && metadata.level() <= self.level(metadata.target())
+        && self.logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match &self.format {
+            Some(format) => self.logger.log_with(record, format),
+            None => self.logger.log_with(record, &write_record),
+        }
+    }
+
+    #[inline(always)]
+    fn flush(&self) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    use log::Log;
+
+    fn log(logger: &dyn Log, target: &str, level: log::Level, args: core::fmt::Arguments<'_>) {
+        logger.log(&log::Record::builder().target(target).level(level).args(args).build());
+    }
+
+    #[test]
+    fn should_filter_records() {
+        let logger = super::builder().tag("Filtered").min_level(LogPriority::DEBUG).filter("hyper=warn, my_app=trace,my_app::net=off,invalid=level").filter("info,hyper=error").build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Trace);
+
+        log(&logger, "hyper::client", log::Level::Warn, format_args!("1"));
+        log(&logger, "hyper::client", log::Level::Error, format_args!("2"));
+        log(&logger, "my_app", log::Level::Debug, format_args!("3"));
+        log(&logger, "my_app", log::Level::Trace, format_args!("4"));
+        log(&logger, "my_app::net", log::Level::Error, format_args!("5"));
+        log(&logger, "other", log::Level::Info, format_args!("6"));
+        log(&logger, "other", log::Level::Debug, format_args!("7"));
+
+        let logs = capture::take();
+        let texts: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(texts, [(6, "Filtered", "2"), (3, "Filtered", "3"), (4, "Filtered", "6")]);
+    }
+
+    #[test]
+    fn should_keep_other_targets_enabled_with_target_directives() {
+        let logger = super::builder().filter("hyper=warn").build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Trace);
+
+        let logger = super::builder().filter("warn,hyper=error").build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Warn);
+        let logger = super::builder().filter("warn,hyper=debug").build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Debug);
+    }
+
+    #[test]
+    fn should_format_records() {
+        let logger = super::builder().format(|writer, record| write!(writer, "[{}] {}", record.level(), record.args())).build();
+        assert_eq!(logger.max_level(), log::LevelFilter::Trace);
+        log(&logger, "Net", log::Level::Info, format_args!("connected"));
+
+        assert_eq!(capture::take(), [(LogPriority::INFO as i32, "Net".into(), "[INFO] connected".into())]);
+    }
+}
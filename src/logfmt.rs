@@ -0,0 +1,182 @@
+use crate::{LogPriority, Writer};
+
+use core::fmt::{self, Write};
+
+#[inline(always)]
+//Returns whether byte requires value to be quoted or is not allowed in key.
+const fn is_special(byte: u8) -> bool {
+    byte <= b' ' || byte == b'=' || byte == b'"' || byte == 0x7f
+}
+
+//Writes value escaped for use in quotes.
+struct Escape<'a, W: Write>(&'a mut W);
+
+impl<W: Write> Write for Escape<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let mut start = 0;
+        for (idx, byte) in text.bytes().enumerate() {
+            let escaped = match byte {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\t' => "\\t",
+                _ => continue,
+            };
+            self.0.write_str(&text[start..idx])?;
+            self.0.write_str(escaped)?;
+            start = idx + 1;
+        }
+        self.0.write_str(&text[start..])
+    }
+}
+
+///Event written in logfmt format, like `db_query ms=12 rows=40 query="SELECT 1"`.
+///
+///Values are quoted when empty or containing spaces, `=`, `"` or control characters.
+///Such characters in keys are replaced with `_`.
+///
+///Event is formatted directly into `Writer`, without allocation, only if its priority is loggable.
+///It is written as single entry by `log`, truncated if necessary, and discarded if dropped without `log`.
+///
+///```rust,no_run
+///use androidy_log::{Event, LogPriority};
+///
+///Event::new(LogPriority::INFO, "db_query").kv("ms", 12).kv("rows", 40).log();
+///```
+pub struct Event {
+    writer: Writer,
+    is_enabled: bool,
+}
+
+impl Event {
+    #[inline]
+    ///Starts event with default tag.
+    pub fn new(prio: LogPriority, name: &str) -> Self {
+        Self::from_writer(Writer::new_default(prio), crate::__is_default_loggable(prio), name)
+    }
+
+    #[inline]
+    ///Starts event with `tag`, truncated to first 23 bytes.
+    pub fn with_tag(tag: &str, prio: LogPriority, name: &str) -> Self {
+        Self::from_writer(Writer::new(tag, prio), crate::is_loggable(prio, tag), name)
+    }
+
+    fn from_writer(mut writer: Writer, is_enabled: bool, name: &str) -> Self {
+        if is_enabled {
+            writer.set_overflow(crate::Overflow::TruncateWithEllipsis);
+            writer.write_data(name.as_bytes());
+        }
+        Self {
+            writer,
+            is_enabled,
+        }
+    }
+
+    ///Adds `key=value` pair.
+    pub fn kv<V: fmt::Display>(mut self, key: &str, value: V) -> Self {
+        if !self.is_enabled {
+            return self;
+        }
+
+        self.writer.write_data(b" ");
+        let mut start = 0;
+        for (idx, byte) in key.bytes().enumerate() {
+            if is_special(byte) {
+                self.writer.write_data(&key.as_bytes()[start..idx]);
+                self.writer.write_data(b"_");
+                start = idx + 1;
+            }
+        }
+        self.writer.write_data(&key.as_bytes()[start..]);
+        self.writer.write_data(b"=");
+
+        //Value is formatted once, and quoted afterwards if necessary
+        let start = self.writer.len;
+        let _ = write!(self.writer, "{}", value);
+        let written = &self.writer.buffer()[start..];
+        if written.is_empty() || written.iter().copied().any(is_special) {
+            let mut value = [0u8; crate::BUFFER_CAPACITY];
+            let value = &mut value[..written.len()];
+            value.copy_from_slice(written);
+            self.writer.len = start;
+            self.writer.truncated = false;
+
+            let _ = self.writer.write_str("\"");
+            let _ = Escape(&mut self.writer).write_str(core::str::from_utf8(value).unwrap_or_default());
+            let _ = self.writer.write_str("\"");
+        }
+        self
+    }
+
+    #[inline]
+    ///Writes event.
+    pub fn log(mut self) {
+        self.writer.flush();
+    }
+}
+
+impl Drop for Event {
+    #[inline]
+    fn drop(&mut self) {
+        //Event is discarded unless written by `log`
+        self.writer.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Event;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_write_logfmt() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        Event::new(LogPriority::INFO, "db_query").kv("ms", 12).kv("rows", 40).log();
+        Event::with_tag("Db", LogPriority::WARN, "error").kv("query", "SELECT \"a\"\n").kv("empty", "").kv("path", "C:\\tmp").kv("eq", "a=b").log();
+        Event::with_tag("Db", LogPriority::INFO, "keys").kv("a b=\"c\"", 1).kv("", 2).log();
+        let _ = Event::new(LogPriority::INFO, "dropped").kv("ms", 12);
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[0], (LogPriority::INFO as i32, "Rust".into(), "db_query ms=12 rows=40".into()));
+        assert_eq!(logs[1], (LogPriority::WARN as i32, "Db".into(), "error query=\"SELECT \\\"a\\\"\\n\" empty=\"\" path=C:\\tmp eq=\"a=b\"".into()));
+        assert_eq!(logs[2], (LogPriority::INFO as i32, "Db".into(), "keys a_b__c_=1 =2".into()));
+    }
+
+    #[test]
+    fn should_write_long_event_as_single_entry() {
+        let value = "a b".repeat(2000);
+        Event::with_tag("Db", LogPriority::ERROR, "long").kv("value", &value).kv("next", 1).log();
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, LogPriority::ERROR as i32);
+        assert!(logs[0].2.starts_with("long value=\"a b"));
+        assert!(logs[0].2.ends_with('…'));
+    }
+
+    #[test]
+    fn should_not_format_event_unless_loggable() {
+        struct Counted<'a>(&'a core::cell::Cell<usize>);
+
+        impl core::fmt::Display for Counted<'_> {
+            fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                fmt.write_str("a b")
+            }
+        }
+
+        let count = core::cell::Cell::new(0);
+        Event::with_tag("Db", LogPriority::INFO, "quoted").kv("value", Counted(&count)).log();
+        assert_eq!(count.get(), 1);
+        assert_eq!(capture::take()[0].2, "quoted value=\"a b\"");
+
+        capture::NOT_LOGGABLE.with(|not_loggable| not_loggable.set(true));
+        Event::with_tag("Db", LogPriority::INFO, "skipped").kv("value", Counted(&count)).log();
+        capture::NOT_LOGGABLE.with(|not_loggable| not_loggable.set(false));
+        assert_eq!(count.get(), 1);
+        assert!(capture::take().is_empty());
+    }
+}
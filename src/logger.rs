@@ -0,0 +1,296 @@
+use crate::{LogPriority, TagHandle, Writer};
+
+use core::cell::UnsafeCell;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Policy to derive tag from record's target, which is module path by default.
+pub enum TargetTag {
+    ///Keeps first 23 bytes, like `my_crate::network::h`.
+    Prefix,
+    ///Keeps last 23 bytes, like `e::network::http::conn`.
+    Suffix,
+    ///Keeps last path segment, truncated to first 23 bytes, like `conn`.
+    LastSegment,
+}
+
+impl TargetTag {
+    //Returns part of target used as tag, before truncation by `Writer`.
+    fn apply<'a>(&self, target: &'a str) -> &'a str {
+        match self {
+            TargetTag::Prefix => target,
+            TargetTag::Suffix => {
+                let mut start = target.len().saturating_sub(crate::TAG_MAX_LEN);
+                while !target.is_char_boundary(start) {
+                    start += 1;
+                }
+                &target[start..]
+            },
+            TargetTag::LastSegment => match target.rfind("::") {
+                Some(idx) => &target[idx + 2..],
+                None => target,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+///Backend of `log` facade, that writes records via `Writer`.
+///
+///Logger holds no state besides tag, hence it requires neither allocation nor locking, and it is
+///available without `std`.
+pub struct LogcatLogger {
+    tag: Option<TagHandle>,
+    target: TargetTag,
+}
+
+impl LogcatLogger {
+    #[inline(always)]
+    ///Creates logger, that uses record's target as tag, truncated to first 23 bytes.
+    ///
+    ///Target can be specified as `log::info!(target: "Audio", ...)`, otherwise it is module path.
+    pub const fn new() -> Self {
+        Self {
+            tag: None,
+            target: TargetTag::Prefix,
+        }
+    }
+
+    #[inline(always)]
+    ///Creates logger, that uses `tag` for all records, truncated to first 23 bytes.
+    pub const fn with_tag(tag: &str) -> Self {
+        Self {
+            tag: Some(TagHandle::new(tag)),
+            target: TargetTag::Prefix,
+        }
+    }
+
+    #[inline(always)]
+    ///Sets policy to derive tag from record's target, unless tag is specified via `with_tag`.
+    ///
+    ///```rust
+    ///use androidy_log::{LogcatLogger, TargetTag};
+    ///
+    ///static LOGGER: LogcatLogger = LogcatLogger::new().with_target_tag(TargetTag::LastSegment);
+    ///```
+    pub const fn with_target_tag(mut self, target: TargetTag) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+impl Default for LogcatLogger {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+pub(crate) const fn level_priority(level: log::Level) -> LogPriority {
+    match level {
+        log::Level::Error => LogPriority::ERROR,
+        log::Level::Warn => LogPriority::WARN,
+        log::Level::Info => LogPriority::INFO,
+        log::Level::Debug => LogPriority::DEBUG,
+        log::Level::Trace => LogPriority::VERBOSE,
+    }
+}
+
+#[cfg(feature = "kv")]
+//Writes key-values as trailing ` key=value` pairs.
+struct KeyValues<'a>(&'a mut dyn Write);
+
+#[cfg(feature = "kv")]
+impl<'kvs> log::kv::VisitSource<'kvs> for KeyValues<'_> {
+    #[inline]
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        let _ = write!(self.0, " {}={}", key, value);
+        Ok(())
+    }
+}
+
+//Writes record's message, followed by its key-values with `kv` feature.
+pub(crate) fn write_record(writer: &mut dyn Write, record: &log::Record<'_>) -> fmt::Result {
+    writer.write_fmt(*record.args())?;
+    #[cfg(feature = "kv")]
+    let _ = record.key_values().visit(&mut KeyValues(writer));
+    Ok(())
+}
+
+impl LogcatLogger {
+    //Writes record using `format`, if it is enabled.
+    pub(crate) fn log_with(&self, record: &log::Record<'_>, format: &dyn Fn(&mut dyn Write, &log::Record<'_>) -> fmt::Result) {
+        if !log::Log::enabled(self, record.metadata()) {
+            return;
+        }
+
+        let prio = level_priority(record.level());
+        let _ = match &self.tag {
+            Some(tag) => format(&mut with_location(tag.writer(prio), record), record),
+            None => format(&mut with_location(Writer::new(self.target.apply(record.target()), prio), record), record),
+        };
+    }
+}
+
+#[inline(always)]
+//Sets location of record, if known.
+fn with_location<T: crate::AsTag>(#[cfg_attr(not(feature = "source-location"), allow(unused_mut))] mut writer: Writer<T>, _record: &log::Record<'_>) -> Writer<T> {
+    #[cfg(feature = "source-location")]
+    if let (Some(file), Some(line)) = (_record.file_static(), _record.line()) {
+        writer.set_location(file, line);
+    }
+    writer
+}
+
+impl log::Log for LogcatLogger {
+    #[inline]
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        let prio = level_priority(metadata.level());
+        match &self.tag {
+            Some(tag) => crate::is_loggable(prio, tag.as_str()),
+            None => crate::is_loggable(prio, self.target.apply(metadata.target())),
+        }
+    }
+
+    #[inline]
+    fn log(&self, record: &log::Record<'_>) {
+        self.log_with(record, &write_record)
+    }
+
+    #[inline(always)]
+    fn flush(&self) {
+    }
+}
+
+static LOGGER: LogcatLogger = LogcatLogger::new();
+
+const UNINIT: u8 = 0;
+const INSTALLING: u8 = 1;
+const INSTALLED: u8 = 2;
+
+//Logger with tag specified at runtime, written only once before installation.
+struct TaggedLogger {
+    state: AtomicU8,
+    logger: UnsafeCell<LogcatLogger>,
+}
+
+unsafe impl Sync for TaggedLogger {}
+
+impl log::Log for TaggedLogger {
+    #[inline(always)]
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        //Only installed once initialized
+        unsafe { &*self.logger.get() }.enabled(metadata)
+    }
+
+    #[inline(always)]
+    fn log(&self, record: &log::Record<'_>) {
+        unsafe { &*self.logger.get() }.log(record)
+    }
+
+    #[inline(always)]
+    fn flush(&self) {
+    }
+}
+
+static TAGGED_LOGGER: TaggedLogger = TaggedLogger {
+    state: AtomicU8::new(UNINIT),
+    logger: UnsafeCell::new(LogcatLogger::new()),
+};
+
+///Installs `LogcatLogger`, that uses record's target as tag, as global logger of `log` facade.
+///
+///Fails if global logger is already installed.
+pub fn init() -> Result<(), log::SetLoggerError> {
+    init_with(&LOGGER)
+}
+
+///Installs `logger` as global logger of `log` facade.
+///
+///Fails if global logger is already installed.
+pub fn init_with(logger: &'static LogcatLogger) -> Result<(), log::SetLoggerError> {
+    log::set_logger(logger)?;
+    log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
+}
+
+///Installs `LogcatLogger`, that uses `tag` for all records, as global logger of `log` facade.
+///
+///Fails if global logger is already installed.
+pub fn init_with_tag(tag: &str) -> Result<(), log::SetLoggerError> {
+    if TAGGED_LOGGER.state.compare_exchange(UNINIT, INSTALLING, Ordering::Acquire, Ordering::Acquire).is_err() {
+        while TAGGED_LOGGER.state.load(Ordering::Acquire) != INSTALLED {
+            core::hint::spin_loop();
+        }
+        //Global logger is installed by now, so it only produces error.
+        return log::set_logger(&LOGGER);
+    }
+
+    unsafe {
+        *TAGGED_LOGGER.logger.get() = LogcatLogger::with_tag(tag);
+    }
+    let result = log::set_logger(&TAGGED_LOGGER);
+    TAGGED_LOGGER.state.store(INSTALLED, Ordering::Release);
+    result?;
+    log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogcatLogger, TargetTag};
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_log_via_facade() {
+        super::init_with_tag("Facade").unwrap();
+        assert!(super::init().is_err());
+        assert!(super::init_with_tag("Other").is_err());
+
+        log::info!(target: "Audio", "started {}", 1);
+        log::trace!("trace");
+
+        let logs = capture::take();
+        assert_eq!(logs, [(LogPriority::INFO as i32, "Facade".into(), "started 1".into()), (LogPriority::VERBOSE as i32, "Facade".into(), "trace".into())]);
+    }
+
+    #[test]
+    fn should_use_target_as_tag() {
+        let logger = LogcatLogger::new();
+        let args = format_args!("started {}", 1);
+        log::Log::log(&logger, &log::Record::builder().target("Audio").level(log::Level::Info).args(args).build());
+        let args = format_args!("trace");
+        log::Log::log(&logger, &log::Record::builder().target("androidy_log::very_long_module_path").level(log::Level::Trace).args(args).build());
+
+        let logs = capture::take();
+        assert_eq!(logs, [(LogPriority::INFO as i32, "Audio".into(), "started 1".into()), (LogPriority::VERBOSE as i32, "androidy_log::very_long".into(), "trace".into())]);
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn should_write_key_values() {
+        let logger = LogcatLogger::with_tag("Net");
+        let kvs: &[(&str, &dyn log::kv::ToValue)] = &[("peer", &"localhost"), ("port", &8080)];
+        let args = format_args!("connected");
+        log::Log::log(&logger, &log::Record::builder().level(log::Level::Info).args(args).key_values(&kvs).build());
+        assert_eq!(capture::take(), [(LogPriority::INFO as i32, "Net".into(), "connected peer=localhost port=8080".into())]);
+    }
+
+    #[test]
+    fn should_derive_tag_from_target() {
+        let target = "androidy_log::ΩΩΩΩΩΩΩΩΩ::conn";
+        assert_eq!(TargetTag::Prefix.apply(target), target);
+        assert_eq!(TargetTag::Suffix.apply(target), "ΩΩΩΩΩΩΩΩ::conn");
+        assert_eq!(TargetTag::Suffix.apply("Audio"), "Audio");
+        assert_eq!(TargetTag::LastSegment.apply(target), "conn");
+        assert_eq!(TargetTag::LastSegment.apply("Audio"), "Audio");
+
+        let logger = LogcatLogger::new().with_target_tag(TargetTag::LastSegment);
+        let args = format_args!("connected");
+        log::Log::log(&logger, &log::Record::builder().target(target).level(log::Level::Info).args(args).build());
+        assert_eq!(capture::take(), [(LogPriority::INFO as i32, "conn".into(), "connected".into())]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Mapped diagnostic context.
+//!
+//! Key-value pairs, that are appended to every message written from current thread, as ` key=value`.
+//! Keys and values are sanitized according to writer's settings, like control characters handling.
+//!
+//! ```rust,no_run
+//! androidy_log::mdc::insert("request_id", 42);
+//! androidy_log::println!("Handling request"); //Handling request request_id=42
+//! androidy_log::mdc::remove("request_id");
+//! ```
+
+use crate::{AsTag, Writer};
+
+use core::cell::RefCell;
+use core::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+std::thread_local! {
+    static CONTEXT: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+///Inserts key-value pair into current thread's context, replacing previous value, if any.
+pub fn insert<K: Into<String>, V: fmt::Display>(key: K, value: V) {
+    let key = key.into();
+    let value = value.to_string();
+
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        match context.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(pair) => pair.1 = value,
+            None => context.push((key, value)),
+        }
+    });
+}
+
+///Returns value of the key in current thread's context.
+pub fn get(key: &str) -> Option<String> {
+    CONTEXT.with(|context| context.borrow().iter().find(|(existing, _)| existing == key).map(|(_, value)| value.clone()))
+}
+
+///Removes key from current thread's context, returning its value.
+pub fn remove(key: &str) -> Option<String> {
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        let idx = context.iter().position(|(existing, _)| existing == key)?;
+        Some(context.remove(idx).1)
+    })
+}
+
+///Removes everything from current thread's context.
+pub fn clear() {
+    CONTEXT.with(|context| context.borrow_mut().clear());
+}
+
+//Appends context to writer's buffer, sanitized as message, as much as it fits.
+pub(crate) fn append<T: AsTag>(writer: &mut Writer<T>) {
+    let _ = CONTEXT.try_with(|context| {
+        for (key, value) in context.borrow().iter() {
+            writer.copy_data(b" ");
+            writer.append_sanitized(key.as_bytes());
+            writer.copy_data(b"=");
+            writer.append_sanitized(value.as_bytes());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ControlChars, LogPriority, Writer};
+    use crate::sys::capture;
+
+    #[test]
+    fn should_append_context_to_messages() {
+        super::insert("request_id", 42);
+        super::insert("user", "guest");
+        super::insert("request_id", 43);
+        assert_eq!(super::get("request_id").as_deref(), Some("43"));
+
+        let mut writer = Writer::new("Mdc", LogPriority::INFO);
+        writer.write_data(b"handling");
+        writer.flush();
+
+        assert_eq!(super::remove("user").as_deref(), Some("guest"));
+        writer.write_data(b"handled");
+        writer.flush();
+
+        super::clear();
+        writer.write_data(b"done");
+        writer.flush();
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["handling request_id=43 user=guest", "handled request_id=43", "done"]);
+    }
+
+    #[test]
+    fn should_sanitize_context_values() {
+        super::insert("user", "\x1b[31mguest\x1b[0m\x07\nadmin");
+
+        let mut writer = Writer::new("Mdc", LogPriority::INFO);
+        writer.set_strip_ansi(true);
+        writer.set_control_chars(ControlChars::Escape);
+        writer.write_data(b"escaped");
+        writer.flush();
+
+        writer.set_control_chars(ControlChars::Strip);
+        writer.write_data(b"stripped");
+        writer.flush();
+
+        writer.set_line_buffered(true);
+        writer.write_data(b"line\n");
+        super::clear();
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["escaped user=guest\\x07\nadmin", "stripped user=guest\nadmin", "line user=guest admin"]);
+    }
+}
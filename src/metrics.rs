@@ -0,0 +1,70 @@
+use crate::LogPriority;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+const PRIORITIES: usize = LogPriority::SILENT as usize + 1;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicUsize = AtomicUsize::new(0);
+static COUNTERS: [AtomicUsize; PRIORITIES] = [ZERO; PRIORITIES];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+///Snapshot of number of messages written since start.
+pub struct Metrics {
+    counts: [usize; PRIORITIES],
+}
+
+impl Metrics {
+    #[inline]
+    ///Returns number of messages written with specified priority.
+    pub const fn count(&self, prio: LogPriority) -> usize {
+        self.counts[prio as usize]
+    }
+
+    #[inline]
+    ///Returns total number of messages written.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+///Returns number of messages written since start.
+///
+///Every entry passed to liblog counts as message, so long messages that are split count multiple times.
+pub fn metrics() -> Metrics {
+    let mut counts = [0; PRIORITIES];
+    for (count, counter) in counts.iter_mut().zip(COUNTERS.iter()) {
+        *count = counter.load(Ordering::Relaxed);
+    }
+
+    Metrics {
+        counts,
+    }
+}
+
+#[inline(always)]
+pub(crate) fn record(prio: LogPriority) {
+    COUNTERS[prio as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LogPriority, Writer};
+
+    #[test]
+    fn should_count_messages_per_priority() {
+        let before = super::metrics();
+
+        let mut writer = Writer::new("Metrics", LogPriority::SILENT);
+        writer.write_data(b"1");
+        writer.flush();
+        writer.flush();
+        writer.write_data(&[b'2'; 4001]);
+        writer.flush();
+
+        let after = super::metrics();
+        assert_eq!(after.count(LogPriority::SILENT) - before.count(LogPriority::SILENT), 3);
+        assert!(after.total() - before.total() >= 3);
+        crate::sys::capture::take();
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{LogPriority, Writer};
+
+use std::io::{self, BufRead};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+///Totals of `pipe_to_log`.
+pub struct PipeStats {
+    ///Number of lines read.
+    pub lines: usize,
+    ///Number of bytes read, including line terminators.
+    pub bytes: usize,
+}
+
+///Streams `reader` into log line by line, until EOF is reached.
+///
+///- `tag` - Log message tag, truncated to first 23 bytes.
+///- `prio` - Logging priority.
+///
+///Each line is written as its own entry, without line terminator.
+///Lines that do not fit into buffer are written as multiple entries, without need to store whole line.
+///
+///Returns totals of read data on EOF or first error.
+pub fn pipe_to_log<R: BufRead>(mut reader: R, tag: &str, prio: LogPriority) -> io::Result<PipeStats> {
+    let mut writer = Writer::new(tag, prio);
+    let mut stats = PipeStats::default();
+    let mut line_started = false;
+    //`\r` at the end of previous chunk, that might be part of `\r\n`
+    let mut pending_cr = false;
+
+    loop {
+        let buffer = match reader.fill_buf() {
+            Ok([]) => break,
+            Ok(buffer) => buffer,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        let consumed = match buffer.iter().position(|byte| *byte == b'\n') {
+            Some(idx) => {
+                let line = &buffer[..idx];
+                if pending_cr && idx > 0 {
+                    writer.write_data(b"\r");
+                }
+                writer.write_data(line.strip_suffix(b"\r").unwrap_or(line));
+                writer.flush();
+                stats.lines += 1;
+                line_started = false;
+                pending_cr = false;
+                idx + 1
+            },
+            None => {
+                if pending_cr {
+                    writer.write_data(b"\r");
+                }
+                pending_cr = buffer.ends_with(b"\r");
+                writer.write_data(buffer.strip_suffix(b"\r").unwrap_or(buffer));
+                line_started = true;
+                buffer.len()
+            },
+        };
+
+        reader.consume(consumed);
+        stats.bytes += consumed;
+    }
+
+    if pending_cr {
+        writer.write_data(b"\r");
+    }
+    if line_started {
+        stats.lines += 1;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pipe_to_log, PipeStats};
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_pipe_reader_lines() {
+        let long_line = "1234567890".repeat(450);
+        let input = format!("first\r\n\nsecond\n{}\nlast", long_line);
+        let reader = std::io::BufReader::with_capacity(16, input.as_bytes());
+
+        let stats = pipe_to_log(reader, "Pipe", LogPriority::INFO).expect("to pipe");
+        assert_eq!(stats, PipeStats { lines: 5, bytes: input.len() });
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["first", "second", &long_line[..4000], &long_line[4000..], "last"]);
+        assert!(logs.iter().all(|(prio, tag, _)| *prio == LogPriority::INFO as i32 && tag == "Pipe"));
+    }
+
+    #[test]
+    fn should_strip_crlf_split_between_reads() {
+        let input = "12345678\r\nnext\r\n1234567\rx\nlast\r";
+        let reader = std::io::BufReader::with_capacity(9, input.as_bytes());
+
+        let stats = pipe_to_log(reader, "Pipe", LogPriority::INFO).expect("to pipe");
+        assert_eq!(stats, PipeStats { lines: 4, bytes: input.len() });
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["12345678", "next", "1234567\rx", "last\r"]);
+    }
+}
@@ -0,0 +1,142 @@
+//! Persistent log, that survives reboot.
+//!
+//! Messages are written into `/dev/pmsg0` via liblog and kept in RAM across reboot, as used by recovery and early boot components.
+//! Each message belongs to file, identified by its name, allowing to store last-gasp diagnostics for the next boot.
+//!
+//! Writing requires access to `/dev/pmsg0`, which is normally available to system components only.
+//!
+//!```rust,no_run
+//!use androidy_log::{pmsg, LogId, LogPriority};
+//!
+//!let _ = pmsg::write_file(LogId::CRASH, LogPriority::FATAL, "last_gasp.txt", b"Out of memory");
+//!
+//!//On next boot
+//!let _ = pmsg::read_files(LogId::CRASH, LogPriority::FATAL, "last_gasp", |file| {
+//!    let _ = (file.name, file.data);
+//!});
+//!```
+
+use crate::{sys, LogId, LogPriority};
+
+use core::fmt;
+
+const NAME_MAX_LEN: usize = 255;
+
+///Failure of persistent log operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PmsgError {
+    ///Persistent log is not supported by this platform.
+    Unavailable,
+    ///File name is longer than 255 bytes or contains null character.
+    InvalidName,
+    ///liblog failed with specified `errno`.
+    Failed(i32),
+}
+
+impl fmt::Display for PmsgError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PmsgError::Unavailable => fmt.write_str("persistent log is not available"),
+            PmsgError::InvalidName => fmt.write_str("invalid persistent log file name"),
+            PmsgError::Failed(errno) => write!(fmt, "persistent log operation failed, errno={}", errno),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PmsgError {}
+
+//Calls `cb` with null terminated `name`.
+fn with_c_name<R>(name: &str, cb: impl FnOnce(&core::ffi::CStr) -> R) -> Result<R, PmsgError> {
+    if name.len() > NAME_MAX_LEN || name.as_bytes().contains(&0) {
+        return Err(PmsgError::InvalidName);
+    }
+
+    let mut buffer = [0u8; NAME_MAX_LEN + 1];
+    buffer[..name.len()].copy_from_slice(name.as_bytes());
+    //Buffer has at least one null character after name
+    Ok(cb(unsafe { core::ffi::CStr::from_bytes_with_nul_unchecked(&buffer[..=name.len()]) }))
+}
+
+//Maps result of liblog.
+fn from_result(result: Option<isize>) -> Result<usize, PmsgError> {
+    match result {
+        Some(result) if result < 0 => Err(PmsgError::Failed(-result as i32)),
+        Some(result) => Ok(result as usize),
+        None => Err(PmsgError::Unavailable),
+    }
+}
+
+///Writes `data` as content of persistent file `name`, replacing its previous content.
+///
+///Wraps `__android_log_pmsg_file_write`, returning number of written bytes.
+///
+///- `log_id` - Log buffer the file belongs to.
+///- `prio` - Priority of the file.
+///- `name` - Name of the file, up to 255 bytes.
+pub fn write_file(log_id: LogId, prio: LogPriority, name: &str, data: &[u8]) -> Result<usize, PmsgError> {
+    from_result(with_c_name(name, |name| sys::pmsg_file_write(log_id as i32, prio as i32, name, data))?)
+}
+
+///File of persistent log, stored during previous boot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PmsgFile<'a> {
+    ///Name of the file.
+    pub name: &'a str,
+    ///Content of the file.
+    pub data: &'a [u8],
+}
+
+///Reads persistent files stored during previous boot, calling `cb` for every file.
+///
+///Wraps `__android_log_pmsg_file_read`, returning its result.
+///Files with name that is not valid UTF-8 are skipped.
+///
+///- `log_id` - Log buffer of files.
+///- `prio` - Minimum priority of files.
+///- `prefix` - Prefix of files' names, up to 255 bytes.
+pub fn read_files<F: FnMut(PmsgFile<'_>)>(log_id: LogId, prio: LogPriority, prefix: &str, mut cb: F) -> Result<usize, PmsgError> {
+    let mut read = |name: &core::ffi::CStr, data: &[u8]| if let Ok(name) = name.to_str() {
+        cb(PmsgFile {
+            name,
+            data,
+        })
+    };
+    from_result(with_c_name(prefix, |prefix| sys::pmsg_file_read(log_id as i32, prio as i32, prefix, &mut read))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_files, write_file, PmsgError, PmsgFile};
+    use crate::{LogId, LogPriority};
+    use crate::sys::capture;
+
+    #[test]
+    fn should_write_persistent_file() {
+        assert_eq!(write_file(LogId::CRASH, LogPriority::FATAL, "last_gasp.txt", b"oom"), Ok(3));
+        assert_eq!(write_file(LogId::CRASH, LogPriority::FATAL, "nul\0", b"oom"), Err(PmsgError::InvalidName));
+        assert_eq!(write_file(LogId::CRASH, LogPriority::FATAL, &"a".repeat(256), b"oom"), Err(PmsgError::InvalidName));
+
+        let files = capture::PMSG.with(|files| files.take());
+        assert_eq!(files, [(LogId::CRASH as i32, LogPriority::FATAL as i32, "last_gasp.txt".into(), b"oom".to_vec())]);
+        assert_eq!(super::from_result(Some(-28)), Err(PmsgError::Failed(28)));
+        assert_eq!(super::from_result(None), Err(PmsgError::Unavailable));
+    }
+
+    #[test]
+    fn should_read_persistent_files() {
+        write_file(LogId::CRASH, LogPriority::ERROR, "crash_1", b"first").unwrap();
+        write_file(LogId::CRASH, LogPriority::ERROR, "crash_2", b"second").unwrap();
+        write_file(LogId::CRASH, LogPriority::ERROR, "other", b"other").unwrap();
+        write_file(LogId::MAIN, LogPriority::ERROR, "crash_3", b"main").unwrap();
+
+        let mut files = Vec::new();
+        let result = read_files(LogId::CRASH, LogPriority::ERROR, "crash_", |file| files.push((file.name.to_owned(), file.data.to_owned())));
+        assert_eq!(result, Ok(11));
+        assert_eq!(files, [("crash_1".into(), b"first".to_vec()), ("crash_2".into(), b"second".to_vec())]);
+
+        let found = read_files(LogId::CRASH, LogPriority::ERROR, "other", |file| assert_eq!(file, PmsgFile { name: "other", data: b"other" }));
+        assert_eq!(found, Ok(5));
+        capture::PMSG.with(|files| files.borrow_mut().clear());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{LogPriority, Writer};
+
+use core::cell::Cell;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::{Arc, Mutex, MutexGuard, Once, OnceLock, Weak};
+use std::time::Instant;
+use std::vec::Vec;
+
+extern "C" {
+    fn atexit(cb: extern "C" fn()) -> i32;
+}
+
+//Thread's line buffer, shared with idle flusher.
+struct Line {
+    writer: Writer,
+    //Time of last write
+    written: Option<Instant>,
+}
+
+//Idle period in milliseconds, 0 if disabled.
+static IDLE_FLUSH: AtomicU64 = AtomicU64::new(0);
+static FLUSHER: OnceLock<Option<std::thread::Thread>> = OnceLock::new();
+static LINES: Mutex<Vec<Weak<Mutex<Line>>>> = Mutex::new(Vec::new());
+
+#[inline]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(error) => error.into_inner(),
+    }
+}
+
+fn new_line(prio: LogPriority) -> Arc<Mutex<Line>> {
+    let mut writer = Writer::new_default(prio);
+    writer.options.line_buffered = true;
+    let line = Arc::new(Mutex::new(Line {
+        writer,
+        written: None,
+    }));
+
+    let mut lines = lock(&LINES);
+    lines.retain(|line| line.strong_count() > 0);
+    lines.push(Arc::downgrade(&line));
+    line
+}
+
+std::thread_local! {
+    static STDOUT: Arc<Mutex<Line>> = new_line(LogPriority::INFO);
+    static STDERR: Arc<Mutex<Line>> = new_line(LogPriority::ERROR);
+    static IS_PRINTING: Cell<bool> = const { Cell::new(false) };
+}
+
+//Marks current thread as writing into its line buffer.
+struct Printing;
+
+impl Printing {
+    #[inline]
+    //Returns `None` if thread is already printing, as message is printed while formatting another one.
+    fn enter() -> Option<Self> {
+        match IS_PRINTING.try_with(|is_printing| is_printing.replace(true)) {
+            Ok(false) => Some(Printing),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for Printing {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = IS_PRINTING.try_with(|is_printing| is_printing.set(false));
+    }
+}
+
+//Writes incomplete lines buffered by current thread.
+pub(crate) fn flush() {
+    let _printing = match Printing::enter() {
+        Some(printing) => printing,
+        None => return,
+    };
+
+    for local in [&STDOUT, &STDERR] {
+        let _ = local.try_with(|line| lock(line).writer.flush());
+    }
+}
+
+extern "C" fn flush_at_exit() {
+    flush();
+}
+
+//Writes incomplete line, if nothing was written into it for `idle` period.
+fn flush_if_idle(line: &Mutex<Line>, idle: Duration) {
+    let mut line = lock(line);
+    if !matches!(line.written, Some(written) if written.elapsed() < idle) {
+        line.writer.flush();
+    }
+}
+
+fn run_flusher() {
+    loop {
+        let idle = IDLE_FLUSH.load(Ordering::Relaxed);
+        if idle == 0 {
+            std::thread::park();
+            continue;
+        }
+
+        let idle = Duration::from_millis(idle);
+        std::thread::sleep(idle);
+        lock(&LINES).retain(|line| match line.upgrade() {
+            Some(line) => {
+                flush_if_idle(&line, idle);
+                true
+            },
+            None => false,
+        });
+    }
+}
+
+///Sets period, after which incomplete lines of `print!` and `eprint!` are written, if nothing is printed.
+///
+///Lines are checked by background thread, started on first call, so they are written after up to twice the period.
+///As they are written from another thread, default tag is used instead of one set via `TagScope`.
+///Disabled by default.
+///
+///Returns whether background thread is running.
+pub fn set_print_idle_flush(idle: Option<Duration>) -> bool {
+    let idle = idle.map_or(0, |idle| core::cmp::max(idle.as_millis(), 1) as u64);
+    IDLE_FLUSH.store(idle, Ordering::Relaxed);
+
+    let flusher = FLUSHER.get_or_init(|| {
+        let flusher = std::thread::Builder::new().name("androidy-log-print".into()).spawn(run_flusher);
+        flusher.ok().map(|flusher| flusher.thread().clone())
+    });
+    match flusher {
+        Some(flusher) => {
+            flusher.unpark();
+            true
+        },
+        None => false,
+    }
+}
+
+#[doc(hidden)]
+//Writes into thread's line buffered writer, used by `print!` and `eprint!`.
+pub fn __print(is_err: bool, args: fmt::Arguments<'_>) {
+    static AT_EXIT: Once = Once::new();
+
+    //Main thread's buffers are not guaranteed to be destroyed on exit
+    AT_EXIT.call_once(|| unsafe {
+        atexit(flush_at_exit);
+    });
+
+    let local = if is_err { &STDERR } else { &STDOUT };
+    let written = Printing::enter().map(|_printing| local.try_with(|line| {
+        let mut line = lock(line);
+        let _ = line.writer.write_fmt(args);
+        //Tracked even if idle flush is disabled, as it might be enabled while line is incomplete
+        line.written = Some(Instant::now());
+    }));
+
+    //Thread is being destroyed or message is printed while formatting another one
+    if written != Some(Ok(())) {
+        let mut writer = Writer::new_default(if is_err { LogPriority::ERROR } else { LogPriority::INFO });
+        let _ = writer.write_fmt(args);
+    }
+}
+
+#[macro_export]
+///`print` alternative, that writes message with INFO priority once line is complete.
+///
+///Text is accumulated by thread local buffer, which is written on every `\n`, thread or process exit.
+///Incomplete line can be also written after idle period, see `set_print_idle_flush`.
+///
+///```rust,no_run
+///for _ in 0..3 {
+///    androidy_log::print!(".");
+///}
+///androidy_log::print!("\n"); //...
+///```
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::__print(false, format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+///`eprint` alternative, that writes message with ERROR priority once line is complete.
+///
+///Text is accumulated by thread local buffer, which is written on every `\n`, thread or process exit.
+///Incomplete line can be also written after idle period, see `set_print_idle_flush`.
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        $crate::__print(true, format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_print_complete_lines() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        std::thread::spawn(|| {
+            for idx in 0..3 {
+                crate::print!("{}", idx);
+            }
+            crate::eprint!("error");
+            assert!(capture::take().is_empty());
+
+            crate::print!("\nnext ");
+            crate::print!("line\nrest");
+            crate::eprint!("\n");
+
+            let logs = capture::take();
+            assert_eq!(logs, [(LogPriority::INFO as i32, "Rust".into(), "012".into()), (LogPriority::INFO as i32, "Rust".into(), "next line".into()), (LogPriority::ERROR as i32, "Rust".into(), "error".into())]);
+        }).join().unwrap();
+    }
+
+    #[test]
+    fn should_flush_incomplete_lines() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        std::thread::spawn(|| {
+            crate::print!("out");
+            crate::eprint!("err");
+            assert!(capture::take().is_empty());
+
+            super::flush();
+            let logs = capture::take();
+            assert_eq!(logs, [(LogPriority::INFO as i32, "Rust".into(), "out".into()), (LogPriority::ERROR as i32, "Rust".into(), "err".into())]);
+
+            super::flush();
+            assert!(capture::take().is_empty());
+        }).join().unwrap();
+    }
+
+    #[test]
+    fn should_flush_idle_lines() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        std::thread::spawn(|| {
+            crate::print!("idle");
+            super::STDOUT.with(|line| {
+                super::flush_if_idle(line, core::time::Duration::from_secs(3600));
+                assert!(capture::take().is_empty());
+
+                super::flush_if_idle(line, core::time::Duration::ZERO);
+            });
+            assert_eq!(capture::take(), [(LogPriority::INFO as i32, "Rust".into(), "idle".into())]);
+        }).join().unwrap();
+    }
+}
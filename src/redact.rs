@@ -0,0 +1,193 @@
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static REVEAL: AtomicBool = AtomicBool::new(false);
+
+const MASK: &str = "***";
+
+#[inline]
+///Sets whether `Redacted` values are to be written as it is.
+///
+///Disabled by default, intended for debugging only.
+pub fn set_reveal_redacted(reveal: bool) {
+    REVEAL.store(reveal, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///Output of `Redacted` value.
+pub enum Redaction {
+    ///Value is replaced with `***`.
+    Mask,
+    ///Value is replaced with FNV-1a hash of its text, like `#811c9dc5`.
+    ///
+    ///Allows to correlate the same values across logs, but it does not hide them: hash is unsalted and
+    ///32-bit, so values with low entropy, like PINs, card digits or phone numbers, are easily recovered
+    ///by brute force. Use `Mask` for such secrets.
+    Hash,
+    ///Only last 4 characters of value are written, like `***1234`.
+    ///
+    ///Values shorter than 5 characters are replaced with `***`, while quotes of `Debug` are not counted.
+    Last4,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+///Wrapper hiding value in logs, unless revealed via `set_reveal_redacted`.
+///
+///Both `Display` and `Debug` are redacted.
+///
+///```rust
+///use androidy_log::Redacted;
+///
+///assert_eq!(Redacted::new("secret").to_string(), "***");
+///assert_eq!(Redacted::last4("4111111111111111").to_string(), "***1111");
+///```
+pub struct Redacted<T> {
+    value: T,
+    redaction: Redaction,
+}
+
+impl<T> Redacted<T> {
+    #[inline(always)]
+    ///Creates wrapper replacing value with `***`.
+    pub const fn new(value: T) -> Self {
+        Self::with_redaction(value, Redaction::Mask)
+    }
+
+    #[inline(always)]
+    ///Creates wrapper replacing value with hash of its text, which only correlates values, see `Redaction::Hash`.
+    pub const fn hashed(value: T) -> Self {
+        Self::with_redaction(value, Redaction::Hash)
+    }
+
+    #[inline(always)]
+    ///Creates wrapper writing only last 4 characters of value.
+    pub const fn last4(value: T) -> Self {
+        Self::with_redaction(value, Redaction::Last4)
+    }
+
+    #[inline(always)]
+    ///Creates wrapper with specified `redaction`.
+    pub const fn with_redaction(value: T, redaction: Redaction) -> Self {
+        Self {
+            value,
+            redaction,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    //Writes redacted text of value, which is `quoted` by `Debug` of strings.
+    fn redact(&self, fmt: &mut fmt::Formatter<'_>, quoted: bool, write: impl Fn(&T, &mut dyn fmt::Write) -> fmt::Result) -> fmt::Result {
+        match self.redaction {
+            Redaction::Mask => fmt.write_str(MASK),
+            Redaction::Hash => {
+                let mut hash = Fnv(0x811c9dc5);
+                write(&self.value, &mut hash)?;
+                write!(fmt, "#{:08x}", hash.0)
+            },
+            Redaction::Last4 => {
+                let mut last = Last4 {
+                    first: '\0',
+                    chars: ['\0'; 5],
+                    len: 0,
+                };
+                write(&self.value, &mut last)?;
+                //Skips closing quote and doesn't count both quotes
+                let (end, len) = match quoted && last.len >= 2 && last.first == '"' && last.chars[(last.len - 1) % 5] == '"' {
+                    true => (last.len - 1, last.len - 2),
+                    false => (last.len, last.len),
+                };
+                if len < 5 {
+                    return fmt.write_str(MASK);
+                }
+
+                fmt.write_str(MASK)?;
+                for idx in end - 4..end {
+                    fmt::Write::write_char(fmt, last.chars[idx % 5])?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+struct Fnv(u32);
+
+impl fmt::Write for Fnv {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for byte in text.bytes() {
+            self.0 = (self.0 ^ byte as u32).wrapping_mul(0x01000193);
+        }
+        Ok(())
+    }
+}
+
+//Keeps first and last 5 characters, so that last 4 are known even if text is quoted
+struct Last4 {
+    first: char,
+    chars: [char; 5],
+    len: usize,
+}
+
+impl fmt::Write for Last4 {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for ch in text.chars() {
+            if self.len == 0 {
+                self.first = ch;
+            }
+            self.chars[self.len % 5] = ch;
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Redacted<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if REVEAL.load(Ordering::Relaxed) {
+            fmt::Display::fmt(&self.value, fmt)
+        } else {
+            self.redact(fmt, false, |value, out| write!(out, "{}", value))
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if REVEAL.load(Ordering::Relaxed) {
+            fmt::Debug::fmt(&self.value, fmt)
+        } else {
+            self.redact(fmt, true, |value, out| write!(out, "{:?}", value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Redacted;
+
+    #[test]
+    fn should_redact_value() {
+        assert_eq!(Redacted::new("secret").to_string(), "***");
+        assert_eq!(format!("{:?}", Redacted::new("secret")), "***");
+        assert_eq!(Redacted::hashed("").to_string(), "#811c9dc5");
+        assert_eq!(Redacted::hashed("a").to_string(), "#e40c292c");
+        assert_eq!(Redacted::last4("ロリ4111111111111234").to_string(), "***1234");
+        assert_eq!(Redacted::last4(12).to_string(), "***");
+        assert_eq!(Redacted::last4("1234").to_string(), "***");
+        assert_eq!(Redacted::last4("12345").to_string(), "***2345");
+        assert_eq!(format!("{:?}", Redacted::last4("1234")), "***");
+        assert_eq!(format!("{:?}", Redacted::last4("12345")), "***2345");
+        assert_eq!(format!("{:?}", Redacted::last4(12345)), "***2345");
+        assert_eq!(Redacted::new(5).into_inner(), 5);
+
+        super::set_reveal_redacted(true);
+        assert_eq!(Redacted::new("secret").to_string(), "secret");
+        assert_eq!(format!("{:?}", Redacted::hashed("secret")), "\"secret\"");
+        super::set_reveal_redacted(false);
+    }
+}
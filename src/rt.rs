@@ -0,0 +1,184 @@
+use crate::{LogId, LogPriority, Writer};
+
+use core::fmt::Write;
+use std::sync::Once;
+
+///Installs panic hook, that writes panic messages using default tag and `FATAL` priority.
+///
+///Messages are written into `LogId::CRASH` buffer.
+///Previously installed hook is replaced.
+///With `panic=abort`, output of stdio is written into log before panic message, as process is about to abort.
+pub fn install_panic_hook() {
+    std::panic::set_hook(std::boxed::Box::new(|info| {
+        if cfg!(panic = "abort") {
+            drain_stdio();
+        }
+        let thread = std::thread::current();
+        let mut writer = Writer::new_default(LogPriority::FATAL);
+        writer.set_log_id(LogId::CRASH);
+        let _ = write!(writer, "thread '{}' {}", thread.name().unwrap_or("<unnamed>"), info);
+    }));
+}
+
+#[cfg(target_os = "android")]
+mod stdio {
+    use std::io;
+    use std::os::unix::io::FromRawFd;
+    use std::string::String;
+    use std::sync::Mutex;
+    use std::thread::JoinHandle;
+    use std::time::{Duration, Instant};
+    use std::vec::Vec;
+
+    //Time to wait for reader, as write end of pipe might be still held by forked child process.
+    const DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+    extern "C" {
+        fn pipe(fds: *mut i32) -> i32;
+        fn dup(fd: i32) -> i32;
+        fn dup2(fd: i32, new_fd: i32) -> i32;
+        fn close(fd: i32) -> i32;
+    }
+
+    struct Redirect {
+        fd: i32,
+        original: i32,
+        reader: JoinHandle<()>,
+    }
+
+    static REDIRECTS: Mutex<Vec<Redirect>> = Mutex::new(Vec::new());
+
+    pub fn redirect(fd: i32, tag: String, prio: crate::LogPriority) -> io::Result<()> {
+        let mut fds = [0; 2];
+        let original = unsafe {
+            if pipe(fds.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original = dup(fd);
+            if original < 0 || dup2(fds[1], fd) < 0 {
+                let error = io::Error::last_os_error();
+                close(fds[0]);
+                close(fds[1]);
+                if original >= 0 {
+                    close(original);
+                }
+                return Err(error);
+            }
+            close(fds[1]);
+            original
+        };
+
+        if fd == 2 {
+            crate::sys::STDERR_FD.store(original, core::sync::atomic::Ordering::Relaxed);
+        }
+
+        let reader = io::BufReader::new(unsafe { std::fs::File::from_raw_fd(fds[0]) });
+        let reader = std::thread::Builder::new().name("androidy-log-stdio".into()).spawn(move || {
+            let _ = crate::pipe_to_log(reader, &tag, prio);
+        })?;
+
+        let redirect = Redirect {
+            fd,
+            original,
+            reader,
+        };
+        match REDIRECTS.lock() {
+            Ok(mut redirects) => redirects.push(redirect),
+            Err(error) => error.into_inner().push(redirect),
+        }
+        Ok(())
+    }
+
+    //Restores original descriptors, waiting for readers to write remaining output up to `DRAIN_TIMEOUT`.
+    pub fn drain() {
+        let redirects = match REDIRECTS.lock() {
+            Ok(mut redirects) => core::mem::take(&mut *redirects),
+            Err(error) => core::mem::take(&mut *error.into_inner()),
+        };
+
+        let current = std::thread::current().id();
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        for redirect in redirects {
+            //Closes write end of pipe, so that reader reaches EOF
+            unsafe {
+                dup2(redirect.original, redirect.fd);
+            }
+            if redirect.reader.thread().id() == current {
+                continue;
+            }
+            while !redirect.reader.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            if redirect.reader.is_finished() {
+                let _ = redirect.reader.join();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "android")]
+///Redirects stdout and stderr into log with `tag`, using `INFO` and `WARN` priorities respectively.
+///
+///Each stream is read by its own thread, which writes every line as separate entry.
+///Original stderr is preserved for failover.
+///
+///Available only on Android.
+pub fn redirect_stdio(tag: &str) -> std::io::Result<()> {
+    stdio::redirect(1, tag.into(), LogPriority::INFO)?;
+    stdio::redirect(2, tag.into(), LogPriority::WARN)
+}
+
+//Writes output buffered by `print!`, stdout and, if redirected, stdio pipes into log.
+pub(crate) fn drain_stdio() {
+    crate::print::flush();
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+    #[cfg(target_os = "android")]
+    stdio::drain();
+}
+
+#[doc(hidden)]
+//Initialization performed by `#[main]` attribute, only once per process.
+pub fn __main_init(tag: Option<&str>) {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        if tag.is_some() {
+            crate::set_default_tag(tag);
+        }
+        install_panic_hook();
+
+        #[cfg(target_os = "android")]
+        {
+            let tag = match tag {
+                Some(tag) => crate::TagHandle::new(tag),
+                #[cfg(feature = "ndk-context")]
+                None => crate::package_tag().copied().unwrap_or(crate::TagHandle::new(crate::DEFAULT_TAG)),
+                #[cfg(not(feature = "ndk-context"))]
+                None => crate::TagHandle::new(crate::DEFAULT_TAG),
+            };
+            let _ = redirect_stdio(tag.as_c_str().to_str().unwrap_or(crate::DEFAULT_TAG));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_log_panic() {
+        super::install_panic_hook();
+        let logs = std::thread::Builder::new().name("panicking".into()).spawn(|| {
+            let _scope = crate::TagScope::enter("Panic");
+            let _ = std::panic::catch_unwind(|| panic!("oops"));
+            (crate::sys::capture::take(), crate::sys::capture::take_log_ids())
+        }).unwrap().join().unwrap();
+        let _ = std::panic::take_hook();
+        let (logs, log_ids) = logs;
+        assert_eq!(log_ids, [crate::LogId::CRASH as i32]);
+
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, crate::LogPriority::FATAL as i32);
+        assert_eq!(logs[0].1, "Panic");
+        assert!(logs[0].2.starts_with("thread 'panicking' panicked at"));
+        assert!(logs[0].2.ends_with("oops"));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{AsTag, TagHandle, Writer};
+use crate::sys;
+
+use core::cell::{Cell, RefCell};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::vec::Vec;
+
+std::thread_local! {
+    static TAGS: RefCell<Vec<TagHandle>> = const { RefCell::new(Vec::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static CORRELATION_ID: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+const MAX_INDENT: usize = 80;
+static INDENT: AtomicUsize = AtomicUsize::new(0);
+static DEFAULT_TAG: RwLock<Option<TagHandle>> = RwLock::new(None);
+
+#[must_use = "Tag is restored once guard is dropped"]
+///Guard overriding default tag within current thread.
+///
+///While guard is alive, writers created via `Writer::new_default` (including logging macros) on
+///current thread use its tag. Previous tag is restored on drop.
+///
+///```rust,no_run
+///use androidy_log::TagScope;
+///
+///let _scope = TagScope::enter("Subsystem");
+///androidy_log::println!("Logged under Subsystem tag");
+///```
+pub struct TagScope {
+    depth: usize,
+    //Tag is bound to thread
+    _thread: PhantomData<*const ()>,
+}
+
+impl TagScope {
+    ///Overrides default tag of current thread with `tag`, truncated to first 23 bytes.
+    pub fn enter(tag: &str) -> Self {
+        let tag = TagHandle::new(tag);
+        let depth = TAGS.with(|tags| {
+            let mut tags = tags.borrow_mut();
+            tags.push(tag);
+            tags.len() - 1
+        });
+
+        Self {
+            depth,
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Drop for TagScope {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = TAGS.try_with(|tags| tags.borrow_mut().truncate(self.depth));
+    }
+}
+
+///Overrides default tag of all threads with `tag`, truncated to first 23 bytes.
+///
+///`TagScope` still takes precedence within its thread, while `None` restores default tag.
+///
+///On Android 11+ `tag` is also set as process-wide default tag of liblog via `__android_log_set_default_tag`,
+///used by other code in the process that logs without tag. liblog's default tag is not restored by `None`.
+pub fn set_default_tag(tag: Option<&str>) {
+    let tag = tag.map(TagHandle::new);
+    if let Some(tag) = tag.as_ref() {
+        sys::set_default_tag(tag.as_c_str());
+    }
+    match DEFAULT_TAG.write() {
+        Ok(mut default) => *default = tag,
+        Err(error) => *error.into_inner() = tag,
+    }
+}
+
+//Calls `cb` with current thread's default tag, if it is overridden.
+pub(crate) fn with_tag<R, F: FnOnce(&TagHandle) -> R>(cb: F) -> Option<R> {
+    match TAGS.try_with(|tags| tags.borrow().last().copied()) {
+        Ok(Some(tag)) => Some(cb(&tag)),
+        _ => {
+            let tag = match DEFAULT_TAG.read() {
+                Ok(tag) => *tag,
+                Err(error) => *error.into_inner(),
+            };
+            tag.as_ref().map(cb)
+        }
+    }
+}
+
+///Sets number of spaces to indent messages with per `IndentScope` depth.
+///
+///Indentation is disabled by default, i.e. `0`.
+///It is limited to 80 spaces regardless of depth.
+pub fn set_indent(width: usize) {
+    INDENT.store(width, Ordering::Relaxed);
+}
+
+#[must_use = "Depth is decreased once guard is dropped"]
+///Guard increasing scope depth of current thread.
+///
+///When indentation is enabled via `set_indent`, every message written on current thread is
+///indented proportionally to the depth, making nested call flows readable.
+pub struct IndentScope {
+    //Depth is bound to thread
+    _thread: PhantomData<*const ()>,
+}
+
+impl IndentScope {
+    #[inline]
+    ///Increases scope depth of current thread.
+    pub fn enter() -> Self {
+        DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Self {
+            _thread: PhantomData,
+        }
+    }
+
+    #[inline]
+    ///Returns current thread's scope depth.
+    pub fn depth() -> usize {
+        DEPTH.try_with(Cell::get).unwrap_or(0)
+    }
+}
+
+impl Drop for IndentScope {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = DEPTH.try_with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+//Writes indentation into writer's buffer.
+pub(crate) fn write_indent<T: AsTag>(writer: &mut Writer<T>) {
+    const SPACES: [u8; MAX_INDENT] = [b' '; MAX_INDENT];
+
+    let width = INDENT.load(Ordering::Relaxed);
+    if width > 0 {
+        let len = IndentScope::depth().saturating_mul(width);
+        writer.copy_data(&SPACES[..core::cmp::min(len, MAX_INDENT)]);
+    }
+}
+
+///Sets correlation id of current thread.
+///
+///When set, every message written on current thread is prefixed with id in hex as `[c:7f3a] `,
+///allowing to stitch together flows spread across multiple threads or JNI calls.
+#[inline]
+pub fn set_correlation_id(id: Option<u64>) {
+    CORRELATION_ID.with(|current| current.set(id));
+}
+
+#[inline]
+///Returns correlation id of current thread.
+pub fn correlation_id() -> Option<u64> {
+    CORRELATION_ID.try_with(Cell::get).ok().flatten()
+}
+
+#[must_use = "Correlation id is restored once guard is dropped"]
+///Guard setting correlation id of current thread, restoring previous one on drop.
+///
+///See `set_correlation_id` for details.
+pub struct CorrelationScope {
+    previous: Option<u64>,
+    //Id is bound to thread
+    _thread: PhantomData<*const ()>,
+}
+
+impl CorrelationScope {
+    #[inline]
+    ///Sets correlation id of current thread.
+    pub fn enter(id: u64) -> Self {
+        Self {
+            previous: CORRELATION_ID.with(|current| current.replace(Some(id))),
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Drop for CorrelationScope {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = CORRELATION_ID.try_with(|current| current.set(self.previous));
+    }
+}
+
+//Writes correlation id into writer's buffer.
+pub(crate) fn write_correlation_id<T: AsTag>(writer: &mut Writer<T>) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    if let Some(mut id) = correlation_id() {
+        let mut buffer = [0u8; 16];
+        let mut cursor = buffer.len();
+        loop {
+            cursor -= 1;
+            buffer[cursor] = HEX[(id & 0xf) as usize];
+            id >>= 4;
+            if id == 0 {
+                break;
+            }
+        }
+
+        writer.copy_data(b"[c:");
+        writer.copy_data(&buffer[cursor..]);
+        writer.copy_data(b"] ");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CorrelationScope, IndentScope, TagScope};
+    use crate::{LogPriority, Writer};
+    use crate::sys::capture;
+
+    #[test]
+    fn should_override_default_tag_within_scope() {
+        let mut tagged = Writer::new("Tagged", LogPriority::INFO);
+        let write_default = |text: &[u8]| {
+            let mut writer = Writer::new_default(LogPriority::INFO);
+            writer.write_data(text);
+        };
+
+        write_default(b"1");
+        {
+            let _outer = TagScope::enter("Outer");
+            write_default(b"2");
+            {
+                let _inner = TagScope::enter("Inner");
+                write_default(b"3");
+                tagged.write_data(b"4");
+                tagged.flush();
+            }
+            write_default(b"5");
+        }
+        write_default(b"6");
+
+        //Tests relying on default tag wait until it is restored
+        let _lock = match capture::DEFAULT_TAG_LOCK.write() {
+            Ok(lock) => lock,
+            Err(error) => error.into_inner(),
+        };
+        super::set_default_tag(Some("Global"));
+        assert_eq!(capture::DEFAULT_TAG.with(|tag| tag.take()).as_deref(), Some("Global"));
+        let other = std::thread::spawn(|| {
+            Writer::new_default(LogPriority::INFO).write_data(b"other");
+            capture::take()
+        }).join().unwrap();
+        assert_eq!(other[0].1, "Global");
+        write_default(b"7");
+        {
+            let _scope = TagScope::enter("Scope");
+            write_default(b"8");
+        }
+        super::set_default_tag(None);
+        write_default(b"9");
+
+        let logs = capture::take();
+        let tags: Vec<_> = logs.iter().map(|(_, tag, text)| (tag.as_str(), text.as_str())).collect();
+        assert_eq!(tags, [("Rust", "1"), ("Outer", "2"), ("Inner", "3"), ("Tagged", "4"), ("Outer", "5"), ("Rust", "6"), ("Global", "7"), ("Scope", "8"), ("Rust", "9")]);
+    }
+
+    #[test]
+    fn should_indent_by_scope_depth() {
+        let indent = capture::set_indent(0);
+        let mut writer = Writer::new("Indent", LogPriority::INFO);
+        let mut write = |text: &[u8]| {
+            writer.write_data(text);
+            writer.flush();
+        };
+
+        write(b"disabled");
+        {
+            let _outer = IndentScope::enter();
+            assert_eq!(IndentScope::depth(), 1);
+            write(b"disabled");
+
+            crate::set_indent(2);
+            write(b"outer");
+            {
+                let _inner = IndentScope::enter();
+                write(b"inner");
+            }
+            write(b"outer");
+        }
+        write(b"top");
+        drop(indent);
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["disabled", "disabled", "  outer", "    inner", "  outer", "top"]);
+    }
+
+    #[test]
+    fn should_prefix_correlation_id() {
+        let mut writer = Writer::new("Correlation", LogPriority::INFO);
+        let mut write = |text: &[u8]| {
+            writer.write_data(text);
+            writer.flush();
+        };
+
+        write(b"none");
+        super::set_correlation_id(Some(0x7f3a));
+        write(b"thread");
+        {
+            let _scope = CorrelationScope::enter(0);
+            assert_eq!(super::correlation_id(), Some(0));
+            write(b"scope");
+        }
+        write(b"thread");
+        super::set_correlation_id(None);
+        write(b"none");
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["none", "[c:7f3a] thread", "[c:0] scope", "[c:7f3a] thread", "none"]);
+    }
+}
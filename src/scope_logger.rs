@@ -0,0 +1,108 @@
+use crate::{HumanDuration, Instant, LogPriority, TagHandle, Writer};
+
+use core::fmt::Write;
+
+///Guard logging entry on creation and exit on drop, useful to trace call flow.
+///
+///With `std` feature, guard holds `IndentScope`, so messages written within it are indented, see `set_indent`.
+///
+///Available without `std` on unix targets.
+///
+///```rust,no_run
+///use androidy_log::ScopeLogger;
+///
+///fn on_create() {
+///    let mut scope = ScopeLogger::new("MyApp", "on_create"); //--> on_create
+///    scope.show_elapsed(true);
+///    //...
+///} //<-- on_create +1.2ms
+///```
+pub struct ScopeLogger<'a> {
+    tag: TagHandle,
+    prio: LogPriority,
+    name: &'a str,
+    start: Instant,
+    show_elapsed: bool,
+    #[cfg(feature = "std")]
+    indent: Option<crate::IndentScope>,
+}
+
+impl<'a> ScopeLogger<'a> {
+    #[inline]
+    ///Logs entry into `name` with DEBUG priority.
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes.
+    ///- `name` - Name of the scope, usually function name.
+    pub fn new(tag: &str, name: &'a str) -> Self {
+        Self::with_priority(tag, name, LogPriority::DEBUG)
+    }
+
+    ///Logs entry into `name` with specified priority.
+    pub fn with_priority(tag: &str, name: &'a str, prio: LogPriority) -> Self {
+        let tag = TagHandle::new(tag);
+        let _ = write!(Writer::with_tag(&tag, prio), "--> {}", name);
+        Self {
+            tag,
+            prio,
+            name,
+            start: Instant::now(),
+            show_elapsed: false,
+            #[cfg(feature = "std")]
+            indent: Some(crate::IndentScope::enter()),
+        }
+    }
+
+    #[inline(always)]
+    ///Sets whether exit message includes time elapsed since entry.
+    pub fn show_elapsed(&mut self, show_elapsed: bool) {
+        self.show_elapsed = show_elapsed;
+    }
+}
+
+impl Drop for ScopeLogger<'_> {
+    fn drop(&mut self) {
+        //Exit is written at the same depth as entry
+        #[cfg(feature = "std")]
+        drop(self.indent.take());
+
+        let mut writer = Writer::with_tag(&self.tag, self.prio);
+        let _ = if self.show_elapsed {
+            write!(writer, "<-- {} +{}", self.name, HumanDuration(self.start.elapsed()))
+        } else {
+            write!(writer, "<-- {}", self.name)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScopeLogger;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_log_entry_and_exit() {
+        #[cfg(feature = "std")]
+        let _indent = capture::set_indent(1);
+        {
+            let _outer = ScopeLogger::new("Trace", "outer");
+            let mut inner = ScopeLogger::with_priority("Trace", "inner", LogPriority::INFO);
+            inner.show_elapsed(true);
+        }
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0], (LogPriority::DEBUG as i32, "Trace".into(), "--> outer".into()));
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(logs[1], (LogPriority::INFO as i32, "Trace".into(), " --> inner".into()));
+            assert!(logs[2].2.starts_with(" <-- inner +"));
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            assert_eq!(logs[1], (LogPriority::INFO as i32, "Trace".into(), "--> inner".into()));
+            assert!(logs[2].2.starts_with("<-- inner +"));
+        }
+        assert_eq!(logs[3], (LogPriority::DEBUG as i32, "Trace".into(), "<-- outer".into()));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::sys;
+
+///Sink of liblog, that writes messages of the whole process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSink {
+    ///Messages are sent to `logd`.
+    ///
+    ///This is default.
+    Logd,
+    ///Messages are written to stderr, in format similar to `logcat`.
+    ///
+    ///Allows to see output of command line binaries and unit tests that are run on device.
+    Stderr,
+}
+
+#[inline]
+///Sets sink of liblog via `__android_log_set_logger`.
+///
+///Returns `false` if it is not supported, which is the case for Android 10 and older or other platforms.
+///
+///```rust,no_run
+///use androidy_log::LogSink;
+///
+///if !androidy_log::set_log_sink(LogSink::Stderr) {
+///    //Fallback to logd
+///}
+///```
+pub fn set_log_sink(sink: LogSink) -> bool {
+    match sink {
+        LogSink::Logd => sys::set_logger(sys::Logger::Logd),
+        LogSink::Stderr => sys::set_logger(sys::Logger::Stderr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_log_sink, LogSink};
+
+    #[test]
+    fn should_not_set_log_sink_on_host() {
+        assert!(!set_log_sink(LogSink::Stderr));
+        assert!(!set_log_sink(LogSink::Logd));
+    }
+}
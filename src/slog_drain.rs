@@ -0,0 +1,117 @@
+use crate::{AsTag, LogPriority, TagHandle, Writer};
+
+use core::fmt::{self, Write};
+
+#[inline]
+const fn level_priority(level: slog::Level) -> LogPriority {
+    match level {
+        slog::Level::Critical => LogPriority::FATAL,
+        slog::Level::Error => LogPriority::ERROR,
+        slog::Level::Warning => LogPriority::WARN,
+        slog::Level::Info => LogPriority::INFO,
+        slog::Level::Debug => LogPriority::DEBUG,
+        slog::Level::Trace => LogPriority::VERBOSE,
+    }
+}
+
+//Writes key-values as trailing ` key=value` pairs.
+struct Pairs<'a, T: AsTag>(&'a mut Writer<T>);
+
+impl<T: AsTag> slog::Serializer for Pairs<'_, T> {
+    #[inline]
+    fn emit_arguments(&mut self, key: slog::Key, value: &fmt::Arguments<'_>) -> slog::Result {
+        let _ = write!(self.0, " {}={}", key, value);
+        Ok(())
+    }
+}
+
+fn write_record<T: AsTag>(mut writer: Writer<T>, record: &slog::Record<'_>, values: &slog::OwnedKVList) {
+    let _ = writer.write_fmt(*record.msg());
+    let _ = slog::KV::serialize(&record.kv(), record, &mut Pairs(&mut writer));
+    let _ = slog::KV::serialize(values, record, &mut Pairs(&mut writer));
+}
+
+///`slog` drain, that writes records via `Writer`.
+///
+///Record is written as its message, followed by record's and logger's key-values as ` key=value` pairs.
+///
+///```rust,no_run
+///let logger = slog::Logger::root(androidy_log::LogcatDrain::new(), slog::o!("version" => "1.0"));
+///slog::info!(logger, "connected"; "peer" => "localhost");
+///```
+pub struct LogcatDrain {
+    tag: Option<TagHandle>,
+}
+
+impl LogcatDrain {
+    #[inline(always)]
+    ///Creates drain, that uses record's tag as tag, or module path if it is empty, truncated to first 23 bytes.
+    pub const fn new() -> Self {
+        Self {
+            tag: None,
+        }
+    }
+
+    #[inline(always)]
+    ///Creates drain, that uses `tag` for all records, truncated to first 23 bytes.
+    pub const fn with_tag(tag: &str) -> Self {
+        Self {
+            tag: Some(TagHandle::new(tag)),
+        }
+    }
+}
+
+impl Default for LogcatDrain {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl slog::Drain for LogcatDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &slog::Record<'_>, values: &slog::OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        let prio = level_priority(record.level());
+        match &self.tag {
+            Some(tag) => if crate::is_loggable(prio, tag.as_str()) {
+                write_record(tag.writer(prio), record, values);
+            },
+            None => {
+                let tag = match record.tag() {
+                    "" => record.module(),
+                    tag => tag,
+                };
+                if crate::is_loggable(prio, tag) {
+                    write_record(Writer::new(tag, prio), record, values);
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogcatDrain;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_write_records() {
+        let logger = slog::Logger::root(LogcatDrain::new(), slog::o!("version" => "1.0"));
+        slog::info!(logger, #"Net", "connected {}", 1; "peer" => "localhost", "port" => 8080);
+        slog::crit!(logger, "failed");
+        let logger = slog::Logger::root(LogcatDrain::with_tag("Slog"), slog::o!());
+        slog::debug!(logger, #"Net", "debug");
+
+        let logs = capture::take();
+        assert_eq!(logs, [
+            (LogPriority::INFO as i32, "Net".into(), "connected 1 port=8080 peer=localhost version=1.0".into()),
+            (LogPriority::FATAL as i32, "androidy_log::slog_drai".into(), "failed version=1.0".into()),
+            (LogPriority::DEBUG as i32, "Slog".into(), "debug".into()),
+        ]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Instant, LogPriority, Writer};
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+///Tag of messages written by `counter!` and `gauge!`.
+pub const METRIC_TAG: &str = "Metrics";
+
+static INTERVAL_MS: AtomicU64 = AtomicU64::new(1000);
+
+#[inline]
+///Sets minimal interval between messages of the same `counter!` or `gauge!`.
+///
+///Default is 1 second.
+pub fn set_metric_interval(interval: Duration) {
+    INTERVAL_MS.store(interval.as_millis() as u64, Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+//State of single `counter!` or `gauge!`.
+pub struct __Metric {
+    //Time of last message in ms, offset by 1 so that 0 means never.
+    last_ms: AtomicU64,
+    pending: AtomicU64,
+}
+
+impl __Metric {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            last_ms: AtomicU64::new(0),
+            pending: AtomicU64::new(0),
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        let now = Instant::now().since_epoch().as_millis() as u64 + 1;
+        let last = self.last_ms.load(Ordering::Relaxed);
+        if last != 0 && now.saturating_sub(last) < INTERVAL_MS.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.last_ms.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+    }
+
+    pub fn count(&self, name: &str, value: u64) {
+        self.pending.fetch_add(value, Ordering::Relaxed);
+        if self.is_due() {
+            let value = self.pending.swap(0, Ordering::Relaxed);
+            write_metric(name, value, "counter");
+        }
+    }
+
+    pub fn gauge<T: fmt::Display>(&self, name: &str, value: T) {
+        if self.is_due() {
+            write_metric(name, value, "gauge");
+        }
+    }
+}
+
+impl Default for __Metric {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn write_metric<T: fmt::Display>(name: &str, value: T, kind: &str) {
+    let mut writer = Writer::new(METRIC_TAG, LogPriority::INFO);
+    let _ = write!(writer, "metric={} value={} type={}", name, value, kind);
+}
+
+#[macro_export]
+///Increments counter `name` by `value` (1 if omitted), writing its total under `METRIC_TAG`.
+///
+///Message, like `metric=frames value=60 type=counter`, is written at most once per
+///interval, set via `set_metric_interval`, with value accumulated since previous message.
+///
+///```rust,no_run
+///androidy_log::counter!("frames");
+///androidy_log::counter!("bytes_sent", 512);
+///```
+macro_rules! counter {
+    ($name:expr) => {
+        $crate::counter!($name, 1)
+    };
+    ($name:expr, $value:expr) => {{
+        static METRIC: $crate::__Metric = $crate::__Metric::new();
+        METRIC.count($name, $value);
+    }};
+}
+
+#[macro_export]
+///Sets gauge `name` to `value`, writing it under `METRIC_TAG`.
+///
+///Message, like `metric=fps value=60 type=gauge`, is written at most once per interval,
+///set via `set_metric_interval`, while values in between are dropped.
+///
+///```rust,no_run
+///androidy_log::gauge!("fps", 60);
+///```
+macro_rules! gauge {
+    ($name:expr, $value:expr) => {{
+        static METRIC: $crate::__Metric = $crate::__Metric::new();
+        METRIC.gauge($name, $value);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LogPriority;
+    use crate::sys::capture;
+    use core::time::Duration;
+
+    #[test]
+    fn should_throttle_metrics() {
+        let count = || crate::counter!("frames");
+        let gauge = |value: f32| crate::gauge!("fps", value);
+
+        count();
+        count();
+        gauge(59.5);
+        gauge(60.0);
+        super::set_metric_interval(Duration::ZERO);
+        count();
+        gauge(61.0);
+        super::set_metric_interval(Duration::from_secs(1));
+
+        let logs = capture::take();
+        let texts: Vec<_> = logs.iter().map(|(prio, tag, text)| {
+            assert_eq!(*prio, LogPriority::INFO as i32);
+            assert_eq!(tag, super::METRIC_TAG);
+            text.as_str()
+        }).collect();
+        assert_eq!(texts, ["metric=frames value=1 type=counter", "metric=fps value=59.5 type=gauge", "metric=frames value=2 type=counter", "metric=fps value=61 type=gauge"]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{HumanDuration, Instant, LogPriority, TagHandle, Writer};
+
+use core::fmt::Write;
+use core::time::Duration;
+
+///Stopwatch logging elapsed time at checkpoints.
+///
+///Available without `std` on unix targets.
+///
+///```rust,no_run
+///use androidy_log::Stopwatch;
+///
+///let mut stopwatch = Stopwatch::new("MyApp", "startup");
+///stopwatch.checkpoint("db ready"); //startup: db ready +12.1ms (total 12.1ms)
+///stopwatch.checkpoint("ui ready"); //startup: ui ready +30.2ms (total 42.3ms)
+///```
+pub struct Stopwatch<'a> {
+    tag: TagHandle,
+    prio: LogPriority,
+    name: &'a str,
+    start: Instant,
+    last: Instant,
+}
+
+impl<'a> Stopwatch<'a> {
+    ///Starts new stopwatch, logging with INFO priority.
+    ///
+    ///- `tag` - Log message tag, truncated to first 23 bytes.
+    ///- `name` - Name of the measured process, used as prefix of every message.
+    pub fn new(tag: &str, name: &'a str) -> Self {
+        let start = Instant::now();
+        Self {
+            tag: TagHandle::new(tag),
+            prio: LogPriority::INFO,
+            name,
+            start,
+            last: start,
+        }
+    }
+
+    #[inline(always)]
+    ///Sets priority of checkpoint messages.
+    pub fn set_priority(&mut self, prio: LogPriority) {
+        self.prio = prio;
+    }
+
+    #[inline]
+    ///Returns time elapsed since start.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    ///Logs time elapsed since previous checkpoint and since start.
+    ///
+    ///Returns time elapsed since previous checkpoint.
+    pub fn checkpoint(&mut self, label: &str) -> Duration {
+        let now = Instant::now();
+        let since_last = now.duration_since(self.last);
+        self.last = now;
+
+        let mut writer = Writer::with_tag(&self.tag, self.prio);
+        let _ = write!(writer, "{}: {} +{} (total {})", self.name, label, HumanDuration(since_last), HumanDuration(now.duration_since(self.start)));
+        since_last
+    }
+}
+
+#[doc(hidden)]
+//Guard of `time_scope!`, logging elapsed time on drop.
+pub struct __TimeScope<'a> {
+    tag: Option<&'a str>,
+    label: &'a str,
+    start: Instant,
+}
+
+impl<'a> __TimeScope<'a> {
+    #[inline(always)]
+    pub fn new(tag: Option<&'a str>, label: &'a str) -> Self {
+        Self {
+            tag,
+            label,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for __TimeScope<'_> {
+    fn drop(&mut self) {
+        if !crate::is_enabled(LogPriority::DEBUG) {
+            return;
+        }
+
+        let elapsed = HumanDuration(self.start.elapsed());
+        let mut writer = match self.tag {
+            Some(tag) => Writer::new(tag, LogPriority::DEBUG),
+            None => Writer::new_default(LogPriority::DEBUG),
+        };
+        let _ = write!(writer, "{} took {}", self.label, elapsed);
+    }
+}
+
+#[macro_export]
+///Logs time elapsed until end of current scope with DEBUG priority, like `load took 12.3ms`.
+///
+///Message is written on any exit from scope, including early return and `?`.
+///
+///```rust,no_run
+///fn load() -> Option<u32> {
+///    androidy_log::time_scope!(tag: "MyApp", "load");
+///    let value = "42".parse().ok()?;
+///    Some(value)
+///}
+///```
+macro_rules! time_scope {
+    (tag: $tag:expr, $label:expr) => {
+        let _time_scope = $crate::__TimeScope::new(Some($tag), $label);
+    };
+    ($label:expr) => {
+        let _time_scope = $crate::__TimeScope::new(None, $label);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stopwatch;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_log_checkpoints() {
+        let mut stopwatch = Stopwatch::new("Timing", "startup");
+        stopwatch.checkpoint("db ready");
+        stopwatch.set_priority(LogPriority::DEBUG);
+        stopwatch.checkpoint("ui ready");
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].0, LogPriority::INFO as i32);
+        assert_eq!(logs[0].1, "Timing");
+        assert!(logs[0].2.starts_with("startup: db ready +"));
+        assert_eq!(logs[1].0, LogPriority::DEBUG as i32);
+        assert!(logs[1].2.starts_with("startup: ui ready +"));
+
+        //Durations are written as `HumanDuration`, with at most single decimal digit
+        let text = logs[1].2.strip_prefix("startup: ui ready +").unwrap();
+        let (since_last, total) = text.split_once(" (total ").unwrap();
+        for duration in [since_last, total.strip_suffix(')').unwrap()] {
+            let number = duration.trim_end_matches(|ch: char| ch.is_alphabetic());
+            assert!(["ns", "µs", "ms", "s"].contains(&&duration[number.len()..]), "{}", duration);
+            assert!(!matches!(number.split_once('.'), Some((_, fraction)) if fraction.len() != 1), "{}", duration);
+        }
+    }
+
+    #[test]
+    fn should_log_time_scope_on_early_return() {
+        fn parse(text: &str) -> Option<u32> {
+            crate::time_scope!(tag: "Timing", "parse");
+            let value = text.parse().ok()?;
+            crate::info!(tag: "Timing", "parsed");
+            Some(value)
+        }
+
+        assert_eq!(parse("x"), None);
+        assert_eq!(parse("1"), Some(1));
+
+        let logs = capture::take();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[0].0, LogPriority::DEBUG as i32);
+        assert_eq!(logs[0].1, "Timing");
+        assert!(logs[0].2.starts_with("parse took "));
+        assert_eq!(logs[1].2, "parsed");
+        assert!(logs[2].2.starts_with("parse took "));
+    }
+}
@@ -0,0 +1,875 @@
+//! Platform specific logging routines.
+//!
+//! On Android messages are written via liblog, while other platforms use host fallback.
+
+use core::ffi::c_char;
+#[cfg(all(target_os = "android", not(test)))]
+use core::{cmp, ffi::c_int};
+
+#[cfg(all(target_os = "android", not(test)))]
+#[link(name = "log")]
+extern "C" {
+    pub fn __android_log_write(prio: i32, tag: *const c_char, text: *const c_char) -> i32;
+    pub fn __android_log_buf_write(log_id: i32, prio: i32, tag: *const c_char, text: *const c_char) -> i32;
+    fn __android_log_print(prio: i32, tag: *const c_char, fmt: *const c_char, ...) -> i32;
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+#[inline]
+//Writes text without need for null terminator, limited by liblog's 1023 bytes buffer.
+pub unsafe fn log_str(prio: i32, tag: *const c_char, text: &str) -> i32 {
+    let len = cmp::min(text.len(), c_int::MAX as usize) as c_int;
+    __android_log_print(prio, tag, b"%.*s\0".as_ptr() as _, len, text.as_ptr())
+}
+
+#[cfg(test)]
+pub unsafe fn log_str(prio: i32, tag: *const c_char, text: &str) -> i32 {
+    let tag = tag_c_str(tag).to_string_lossy().into_owned();
+    let _ = capture::LOGS.try_with(|logs| logs.borrow_mut().push((prio, tag, text.to_owned())));
+    0
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+pub unsafe fn log_str(prio: i32, tag: *const c_char, text: &str) -> i32 {
+    host::write(prio, tag_c_str(tag).to_bytes(), text.as_bytes())
+}
+
+#[inline]
+//Returns tag as C string, treating null as empty tag.
+pub unsafe fn tag_c_str<'a>(tag: *const c_char) -> &'a core::ffi::CStr {
+    if tag.is_null() {
+        core::ffi::CStr::from_bytes_with_nul_unchecked(b"\0")
+    } else {
+        core::ffi::CStr::from_ptr(tag)
+    }
+}
+
+#[cfg(test)]
+pub unsafe fn __android_log_write(prio: i32, tag: *const c_char, text: *const c_char) -> i32 {
+    let tag = tag_c_str(tag).to_string_lossy().into_owned();
+    let text = core::ffi::CStr::from_ptr(text).to_string_lossy().into_owned();
+    let _ = capture::LOGS.try_with(|logs| logs.borrow_mut().push((prio, tag, text)));
+    0
+}
+
+#[cfg(test)]
+pub unsafe fn __android_log_buf_write(log_id: i32, prio: i32, tag: *const c_char, text: *const c_char) -> i32 {
+    let _ = capture::LOG_IDS.try_with(|ids| ids.borrow_mut().push(log_id));
+    __android_log_write(prio, tag, text)
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+//Host has single output, so buffer is ignored
+pub unsafe fn __android_log_buf_write(_log_id: i32, prio: i32, tag: *const c_char, text: *const c_char) -> i32 {
+    __android_log_write(prio, tag, text)
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+pub unsafe fn __android_log_write(prio: i32, tag: *const c_char, text: *const c_char) -> i32 {
+    let tag = tag_c_str(tag).to_bytes();
+    let text = core::ffi::CStr::from_ptr(text).to_bytes();
+    host::write(prio, tag, text)
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+#[inline]
+//Writes binary event with already encoded payload.
+pub fn event_write(tag: i32, payload: &[u8]) -> i32 {
+    extern "C" {
+        fn __android_log_bwrite(tag: i32, payload: *const core::ffi::c_void, len: usize) -> i32;
+    }
+
+    unsafe {
+        __android_log_bwrite(tag, payload.as_ptr() as _, payload.len())
+    }
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+#[inline]
+//Writes binary event with payload of specified type.
+pub fn event_write_typed(tag: i32, kind: u8, payload: &[u8]) -> i32 {
+    extern "C" {
+        fn __android_log_btwrite(tag: i32, kind: c_char, payload: *const core::ffi::c_void, len: usize) -> i32;
+    }
+
+    unsafe {
+        __android_log_btwrite(tag, kind as _, payload.as_ptr() as _, payload.len())
+    }
+}
+
+#[cfg(test)]
+pub fn event_write(tag: i32, payload: &[u8]) -> i32 {
+    let _ = capture::EVENTS.try_with(|events| events.borrow_mut().push((tag, payload.to_owned())));
+    0
+}
+
+#[cfg(test)]
+pub fn event_write_typed(tag: i32, kind: u8, payload: &[u8]) -> i32 {
+    let mut data = vec![kind];
+    data.extend_from_slice(payload);
+    event_write(tag, &data)
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+//Binary events cannot be represented by host output
+pub fn event_write(_tag: i32, _payload: &[u8]) -> i32 {
+    0
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+pub fn event_write_typed(_tag: i32, _kind: u8, _payload: &[u8]) -> i32 {
+    0
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Writes binary event into security buffer, if liblog exports it.
+pub fn security_event_write(tag: i32, payload: &[u8]) -> Option<i32> {
+    type SecurityWrite = unsafe extern "C" fn(tag: i32, payload: *const core::ffi::c_void, len: usize) -> i32;
+    static SECURITY_WRITE: dl::Symbol = dl::Symbol::new(b"__android_log_security_bwrite\0");
+
+    SECURITY_WRITE.get().map(|addr| unsafe {
+        let security_write: SecurityWrite = core::mem::transmute(addr);
+        security_write(tag, payload.as_ptr() as _, payload.len())
+    })
+}
+
+#[cfg(test)]
+pub fn security_event_write(tag: i32, payload: &[u8]) -> Option<i32> {
+    let _ = capture::SECURITY_EVENTS.try_with(|events| events.borrow_mut().push((tag, payload.to_owned())));
+    Some(0)
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline]
+pub fn security_event_write(_tag: i32, _payload: &[u8]) -> Option<i32> {
+    None
+}
+
+#[cfg(all(feature = "source-location", target_os = "android", not(test)))]
+//Writes message with source location, if liblog supports it.
+pub unsafe fn log_message(log_id: i32, prio: i32, tag: *const c_char, file: &str, line: u32, text: *const c_char) -> Option<i32> {
+    const FILE_MAX_LEN: usize = 255;
+
+    #[repr(C)]
+    struct LogMessage {
+        struct_size: usize,
+        buffer_id: i32,
+        priority: i32,
+        tag: *const c_char,
+        file: *const c_char,
+        line: u32,
+        message: *const c_char,
+    }
+
+    type WriteLogMessage = unsafe extern "C" fn(message: *mut LogMessage);
+    static WRITE_LOG_MESSAGE: dl::Symbol = dl::Symbol::new(b"__android_log_write_log_message\0");
+
+    let write_log_message: WriteLogMessage = core::mem::transmute(WRITE_LOG_MESSAGE.get()?);
+
+    //Unlike `__android_log_buf_write`, it doesn't check whether message is loggable
+    if !is_loggable(prio, tag_c_str(tag).to_str().unwrap_or_default()) {
+        //Same result as liblog returns for filtered message
+        return Some(-1);
+    }
+
+    //Keep end of path, as it is the most specific part
+    let mut start = file.len().saturating_sub(FILE_MAX_LEN);
+    while !file.is_char_boundary(start) {
+        start += 1;
+    }
+    let file = &file.as_bytes()[start..];
+    let mut file_buffer = [0u8; FILE_MAX_LEN + 1];
+    file_buffer[..file.len()].copy_from_slice(file);
+
+    let mut message = LogMessage {
+        struct_size: core::mem::size_of::<LogMessage>(),
+        buffer_id: log_id,
+        priority: prio,
+        tag,
+        file: file_buffer.as_ptr() as _,
+        line,
+        message: text,
+    };
+    write_log_message(&mut message);
+    //liblog doesn't report result of write, so it is assumed to succeed
+    Some(0)
+}
+
+#[cfg(all(feature = "source-location", test))]
+pub unsafe fn log_message(_log_id: i32, _prio: i32, _tag: *const c_char, file: &str, line: u32, _text: *const c_char) -> Option<i32> {
+    let _ = capture::LOCATIONS.try_with(|locations| locations.borrow_mut().push((file.to_owned(), line)));
+    //Captured the same way as without location
+    None
+}
+
+#[cfg(all(feature = "source-location", not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub unsafe fn log_message(_log_id: i32, _prio: i32, _tag: *const c_char, _file: &str, _line: u32, _text: *const c_char) -> Option<i32> {
+    None
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Lazily resolved optional symbols, missing on older Android versions.
+pub mod dl {
+    use core::ffi::{c_char, c_void};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[cfg(target_pointer_width = "64")]
+    const RTLD_DEFAULT: *mut c_void = 0 as _;
+    #[cfg(target_pointer_width = "32")]
+    const RTLD_DEFAULT: *mut c_void = 0xffffffffusize as _;
+
+    const UNRESOLVED: usize = 0;
+    const MISSING: usize = 1;
+
+    #[link(name = "dl")]
+    extern "C" {
+        fn dlsym(handle: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    pub struct Symbol {
+        //Null terminated name.
+        name: &'static [u8],
+        addr: AtomicUsize,
+    }
+
+    impl Symbol {
+        pub const fn new(name: &'static [u8]) -> Self {
+            Self {
+                name,
+                addr: AtomicUsize::new(UNRESOLVED),
+            }
+        }
+
+        //Returns address of symbol, if available.
+        pub fn get(&self) -> Option<usize> {
+            let addr = match self.addr.load(Ordering::Acquire) {
+                UNRESOLVED => {
+                    let addr = unsafe { dlsym(RTLD_DEFAULT, self.name.as_ptr() as _) } as usize;
+                    let addr = if addr == 0 { MISSING } else { addr };
+                    self.addr.store(addr, Ordering::Release);
+                    addr
+                },
+                addr => addr,
+            };
+
+            match addr {
+                MISSING => None,
+                addr => Some(addr),
+            }
+        }
+    }
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Consults system's log filter, available since Android 11.
+pub fn is_loggable(prio: i32, tag: &str) -> bool {
+    type IsLoggable = unsafe extern "C" fn(prio: i32, tag: *const c_char, len: usize, default_prio: i32) -> i32;
+    static IS_LOGGABLE: dl::Symbol = dl::Symbol::new(b"__android_log_is_loggable_len\0");
+
+    match IS_LOGGABLE.get() {
+        //Same default as liblog uses when writing messages
+        Some(addr) => unsafe {
+            let is_loggable: IsLoggable = core::mem::transmute(addr);
+            is_loggable(prio, tag.as_ptr() as _, tag.len(), crate::LogPriority::VERBOSE as i32) != 0
+        },
+        None => true,
+    }
+}
+
+#[cfg(test)]
+pub fn is_loggable(_prio: i32, tag: &str) -> bool {
+    let _ = capture::LOGGABLE_TAGS.try_with(|tags| tags.borrow_mut().push(tag.to_owned()));
+    !capture::NOT_LOGGABLE.with(|not_loggable| not_loggable.get())
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub fn is_loggable(_prio: i32, _tag: &str) -> bool {
+    true
+}
+
+#[cfg(all(any(unix, feature = "std"), target_os = "android", not(test)))]
+//Sets aborter of liblog that calls crate's aborter, if supported.
+pub fn set_aborter() -> bool {
+    type Aborter = unsafe extern "C" fn(message: *const c_char);
+    type SetAborter = unsafe extern "C" fn(aborter: Aborter);
+    static SET_ABORTER: dl::Symbol = dl::Symbol::new(b"__android_log_set_aborter\0");
+    static DEFAULT_ABORTER: dl::Symbol = dl::Symbol::new(b"__android_log_default_aborter\0");
+
+    unsafe extern "C" fn aborter(message: *const c_char) {
+        crate::abort::call_aborter(tag_c_str(message).to_str().unwrap_or_default());
+
+        match DEFAULT_ABORTER.get() {
+            Some(addr) => {
+                let default_aborter: Aborter = core::mem::transmute(addr);
+                default_aborter(message);
+            },
+            None => abort(),
+        }
+    }
+
+    match SET_ABORTER.get() {
+        Some(addr) => unsafe {
+            let set_aborter: SetAborter = core::mem::transmute(addr);
+            set_aborter(aborter);
+            true
+        },
+        None => false,
+    }
+}
+
+#[cfg(all(any(unix, feature = "std"), any(not(target_os = "android"), test)))]
+#[inline(always)]
+pub fn set_aborter() -> bool {
+    false
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Writes persistent file, if liblog supports it.
+pub fn pmsg_file_write(log_id: i32, prio: i32, name: &core::ffi::CStr, data: &[u8]) -> Option<isize> {
+    type PmsgFileWrite = unsafe extern "C" fn(log_id: i32, prio: c_char, name: *const c_char, data: *const c_char, len: usize) -> isize;
+    static PMSG_FILE_WRITE: dl::Symbol = dl::Symbol::new(b"__android_log_pmsg_file_write\0");
+
+    PMSG_FILE_WRITE.get().map(|addr| unsafe {
+        let pmsg_file_write: PmsgFileWrite = core::mem::transmute(addr);
+        pmsg_file_write(log_id, prio as c_char, name.as_ptr(), data.as_ptr() as _, data.len())
+    })
+}
+
+#[cfg(test)]
+pub fn pmsg_file_write(log_id: i32, prio: i32, name: &core::ffi::CStr, data: &[u8]) -> Option<isize> {
+    let name = name.to_string_lossy().into_owned();
+    let _ = capture::PMSG.try_with(|files| files.borrow_mut().push((log_id, prio, name, data.to_owned())));
+    Some(data.len() as isize)
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Reads persistent files, if liblog supports it.
+pub fn pmsg_file_read(log_id: i32, prio: i32, prefix: &core::ffi::CStr, cb: &mut dyn FnMut(&core::ffi::CStr, &[u8])) -> Option<isize> {
+    type ReadFn = unsafe extern "C" fn(log_id: i32, prio: c_char, name: *const c_char, data: *const c_char, len: usize, arg: *mut core::ffi::c_void) -> isize;
+    type PmsgFileRead = unsafe extern "C" fn(log_id: i32, prio: c_char, prefix: *const c_char, read: ReadFn, arg: *mut core::ffi::c_void) -> isize;
+    static PMSG_FILE_READ: dl::Symbol = dl::Symbol::new(b"__android_log_pmsg_file_read\0");
+
+    unsafe extern "C" fn read(_log_id: i32, _prio: c_char, name: *const c_char, data: *const c_char, len: usize, arg: *mut core::ffi::c_void) -> isize {
+        let cb = &mut *(arg as *mut &mut dyn FnMut(&core::ffi::CStr, &[u8]));
+        let data = match data.is_null() {
+            true => &[][..],
+            false => core::slice::from_raw_parts(data.cast(), len),
+        };
+        cb(tag_c_str(name), data);
+        len as isize
+    }
+
+    PMSG_FILE_READ.get().map(|addr| unsafe {
+        let pmsg_file_read: PmsgFileRead = core::mem::transmute(addr);
+        let mut cb = cb;
+        pmsg_file_read(log_id, prio as c_char, prefix.as_ptr(), read, &mut cb as *mut &mut dyn FnMut(&core::ffi::CStr, &[u8]) as _)
+    })
+}
+
+#[cfg(test)]
+pub fn pmsg_file_read(log_id: i32, prio: i32, prefix: &core::ffi::CStr, cb: &mut dyn FnMut(&core::ffi::CStr, &[u8])) -> Option<isize> {
+    let prefix = prefix.to_string_lossy();
+    let files = capture::PMSG.with(|files| files.borrow().clone());
+    let mut len = 0;
+    for (file_log_id, file_prio, name, data) in files {
+        if file_log_id == log_id && file_prio >= prio && name.starts_with(&*prefix) {
+            let name = std::ffi::CString::new(name).unwrap();
+            cb(&name, &data);
+            len += data.len() as isize;
+        }
+    }
+    Some(len)
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub fn pmsg_file_write(_log_id: i32, _prio: i32, _name: &core::ffi::CStr, _data: &[u8]) -> Option<isize> {
+    None
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub fn pmsg_file_read(_log_id: i32, _prio: i32, _prefix: &core::ffi::CStr, _cb: &mut dyn FnMut(&core::ffi::CStr, &[u8])) -> Option<isize> {
+    None
+}
+
+//Loggers of liblog.
+pub enum Logger {
+    Logd,
+    Stderr,
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Sets logger of liblog, if supported.
+pub fn set_logger(logger: Logger) -> bool {
+    type SetLogger = unsafe extern "C" fn(logger: usize);
+    static SET_LOGGER: dl::Symbol = dl::Symbol::new(b"__android_log_set_logger\0");
+    static LOGD_LOGGER: dl::Symbol = dl::Symbol::new(b"__android_log_logd_logger\0");
+    static STDERR_LOGGER: dl::Symbol = dl::Symbol::new(b"__android_log_stderr_logger\0");
+
+    let logger = match logger {
+        Logger::Logd => LOGD_LOGGER.get(),
+        Logger::Stderr => STDERR_LOGGER.get(),
+    };
+
+    match (SET_LOGGER.get(), logger) {
+        (Some(addr), Some(logger)) => unsafe {
+            let set_logger: SetLogger = core::mem::transmute(addr);
+            set_logger(logger);
+            true
+        },
+        _ => false,
+    }
+}
+
+#[cfg(any(not(target_os = "android"), test))]
+#[inline(always)]
+pub fn set_logger(_logger: Logger) -> bool {
+    false
+}
+
+#[cfg(all(feature = "std", target_os = "android", not(test)))]
+//Sets process-wide default tag, if liblog supports it.
+pub fn set_default_tag(tag: &core::ffi::CStr) {
+    type SetDefaultTag = unsafe extern "C" fn(tag: *const c_char);
+    static SET_DEFAULT_TAG: dl::Symbol = dl::Symbol::new(b"__android_log_set_default_tag\0");
+
+    if let Some(addr) = SET_DEFAULT_TAG.get() {
+        //liblog copies tag
+        unsafe {
+            let set_default_tag: SetDefaultTag = core::mem::transmute(addr);
+            set_default_tag(tag.as_ptr());
+        }
+    }
+}
+
+#[cfg(all(feature = "std", test))]
+pub fn set_default_tag(tag: &core::ffi::CStr) {
+    let _ = capture::DEFAULT_TAG.try_with(|default| *default.borrow_mut() = Some(tag.to_string_lossy().into_owned()));
+}
+
+#[cfg(all(feature = "std", not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub fn set_default_tag(_tag: &core::ffi::CStr) {
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Sets process-wide minimum priority, if liblog supports it.
+pub fn set_minimum_priority(prio: i32) {
+    type SetMinimumPriority = unsafe extern "C" fn(prio: i32) -> i32;
+    static SET_MINIMUM_PRIORITY: dl::Symbol = dl::Symbol::new(b"__android_log_set_minimum_priority\0");
+
+    if let Some(addr) = SET_MINIMUM_PRIORITY.get() {
+        //Unlike UNKNOWN, DEFAULT resets minimum priority
+        let prio = core::cmp::max(prio, crate::LogPriority::DEFAULT as i32);
+        unsafe {
+            let set_minimum_priority: SetMinimumPriority = core::mem::transmute(addr);
+            set_minimum_priority(prio);
+        }
+    }
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+//Returns process-wide minimum priority, if liblog supports it.
+pub fn minimum_priority() -> Option<i32> {
+    type GetMinimumPriority = unsafe extern "C" fn() -> i32;
+    static GET_MINIMUM_PRIORITY: dl::Symbol = dl::Symbol::new(b"__android_log_get_minimum_priority\0");
+
+    GET_MINIMUM_PRIORITY.get().map(|addr| unsafe {
+        let get_minimum_priority: GetMinimumPriority = core::mem::transmute(addr);
+        get_minimum_priority()
+    })
+}
+
+#[cfg(any(not(target_os = "android"), test))]
+#[inline(always)]
+pub fn set_minimum_priority(_prio: i32) {
+}
+
+#[cfg(any(not(target_os = "android"), test))]
+#[inline(always)]
+pub fn minimum_priority() -> Option<i32> {
+    None
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+extern "C" {
+    fn __system_property_get(name: *const c_char, value: *mut c_char) -> i32;
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+pub fn is_debuggable() -> bool {
+    //PROP_VALUE_MAX
+    let mut value = [0u8; 92];
+    let len = unsafe {
+        __system_property_get(b"ro.debuggable\0".as_ptr() as _, value.as_mut_ptr() as _)
+    };
+    len == 1 && value[0] == b'1'
+}
+
+#[cfg(any(not(target_os = "android"), test))]
+#[inline(always)]
+pub fn is_debuggable() -> bool {
+    true
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+#[inline(always)]
+pub fn set_abort_message(text: &core::ffi::CStr) {
+    extern "C" {
+        fn android_set_abort_message(text: *const c_char);
+    }
+
+    unsafe {
+        android_set_abort_message(text.as_ptr())
+    }
+}
+
+#[cfg(test)]
+pub fn set_abort_message(text: &core::ffi::CStr) {
+    let _ = capture::ABORT_MESSAGE.try_with(|message| *message.borrow_mut() = Some(text.to_string_lossy().into_owned()));
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+#[inline(always)]
+pub fn set_abort_message(_text: &core::ffi::CStr) {
+}
+
+#[cfg(all(target_os = "android", not(test)))]
+#[inline(always)]
+pub unsafe fn log_assert(cond: &core::ffi::CStr, tag: *const c_char, text: &core::ffi::CStr) -> ! {
+    extern "C" {
+        fn __android_log_assert(cond: *const c_char, tag: *const c_char, fmt: *const c_char, ...) -> !;
+    }
+
+    __android_log_assert(cond.as_ptr(), tag, b"%s\0".as_ptr() as _, text.as_ptr())
+}
+
+#[cfg(all(any(unix, feature = "std"), any(not(target_os = "android"), test)))]
+//Same as liblog does, except for writing into stderr
+pub unsafe fn log_assert(_cond: &core::ffi::CStr, tag: *const c_char, text: &core::ffi::CStr) -> ! {
+    set_abort_message(text);
+    __android_log_write(7, tag, text.as_ptr());
+    abort()
+}
+
+#[cfg(feature = "std")]
+#[inline(always)]
+pub fn abort() -> ! {
+    std::process::abort()
+}
+
+#[cfg(all(unix, not(feature = "std")))]
+#[inline(always)]
+pub fn abort() -> ! {
+    extern "C" {
+        fn abort() -> !;
+    }
+
+    unsafe {
+        abort()
+    }
+}
+
+//Logcat's brief format priority letter.
+#[cfg(any(feature = "std", all(windows, feature = "output-debug-string", not(target_os = "android"), not(test))))]
+const fn prio_char(prio: i32) -> u8 {
+    match prio {
+        2 => b'V',
+        3 => b'D',
+        4 => b'I',
+        5 => b'W',
+        6 => b'E',
+        7 => b'F',
+        8 => b'S',
+        _ => b'?',
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "android"))]
+//Original stderr, which is replaced when stdio is redirected into log.
+pub static STDERR_FD: core::sync::atomic::AtomicI32 = core::sync::atomic::AtomicI32::new(2);
+
+#[cfg(feature = "std")]
+///Writes message to stderr using format similar to logcat's brief format.
+pub fn write_stderr(prio: i32, tag: &[u8], text: &[u8]) {
+    use std::io::Write;
+
+    #[cfg(target_os = "android")]
+    let mut stderr = core::mem::ManuallyDrop::new(unsafe {
+        <std::fs::File as std::os::unix::io::FromRawFd>::from_raw_fd(STDERR_FD.load(core::sync::atomic::Ordering::Relaxed))
+    });
+    #[cfg(not(target_os = "android"))]
+    let mut stderr = std::io::stderr().lock();
+    let _ = stderr.write_all(&[prio_char(prio), b'/']);
+    let _ = stderr.write_all(tag);
+    let _ = stderr.write_all(b": ");
+    let _ = stderr.write_all(text);
+    let _ = stderr.write_all(b"\n");
+}
+
+#[cfg(all(not(target_os = "android"), not(test)))]
+mod host {
+    #[cfg(all(windows, feature = "output-debug-string"))]
+    use super::prio_char;
+
+    #[cfg_attr(not(any(feature = "std", all(windows, feature = "output-debug-string"))), allow(unused_variables))]
+    pub fn write(prio: i32, tag: &[u8], text: &[u8]) -> i32 {
+        #[cfg(all(windows, feature = "output-debug-string"))]
+        debug_string::write(prio_char(prio), tag, text);
+
+        #[cfg(feature = "std")]
+        super::write_stderr(prio, tag, text);
+
+        0
+    }
+
+    #[cfg(all(windows, feature = "output-debug-string"))]
+    mod debug_string {
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn OutputDebugStringW(text: *const u16);
+        }
+
+        //Encodes UTF-8 text into UTF-16 by chunks, replacing invalid sequences.
+        struct Utf16Buffer {
+            buffer: [u16; 256],
+            len: usize,
+        }
+
+        impl Utf16Buffer {
+            fn push_str(&mut self, text: &str) {
+                for ch in text.chars() {
+                    if self.len + 2 >= self.buffer.len() {
+                        self.flush();
+                    }
+                    self.len += ch.encode_utf16(&mut self.buffer[self.len..]).len();
+                }
+            }
+
+            fn push_bytes(&mut self, bytes: &[u8]) {
+                for chunk in bytes.utf8_chunks() {
+                    self.push_str(chunk.valid());
+                    if !chunk.invalid().is_empty() {
+                        self.push_str(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]));
+                    }
+                }
+            }
+
+            fn flush(&mut self) {
+                if self.len > 0 {
+                    self.buffer[self.len] = 0;
+                    unsafe {
+                        OutputDebugStringW(self.buffer.as_ptr());
+                    }
+                    self.len = 0;
+                }
+            }
+        }
+
+        pub fn write(prio: u8, tag: &[u8], text: &[u8]) {
+            let mut buffer = Utf16Buffer {
+                buffer: [0; 256],
+                len: 0,
+            };
+
+            buffer.push_bytes(&[prio, b'/']);
+            buffer.push_bytes(tag);
+            buffer.push_bytes(b": ");
+            buffer.push_bytes(text);
+            buffer.push_bytes(b"\n");
+            buffer.flush();
+        }
+    }
+}
+
+#[cfg(all(feature = "atrace", target_os = "android", not(test)))]
+pub mod atrace {
+    //! ATrace sections, available since Android 6 and counters since Android 10.
+
+    use super::dl::Symbol;
+
+    use core::ffi::c_char;
+
+    static IS_ENABLED: Symbol = Symbol::new(b"ATrace_isEnabled\0");
+    static BEGIN_SECTION: Symbol = Symbol::new(b"ATrace_beginSection\0");
+    static END_SECTION: Symbol = Symbol::new(b"ATrace_endSection\0");
+    static SET_COUNTER: Symbol = Symbol::new(b"ATrace_setCounter\0");
+
+    //Copies name into null terminated buffer, truncating it.
+    fn with_c_name<R>(name: &str, cb: impl FnOnce(*const c_char) -> R) -> R {
+        let mut buffer = [0u8; 128];
+        let len = core::cmp::min(name.len(), buffer.len() - 1);
+        buffer[..len].copy_from_slice(&name.as_bytes()[..len]);
+        cb(buffer.as_ptr() as _)
+    }
+
+    pub fn is_enabled() -> bool {
+        match IS_ENABLED.get() {
+            Some(addr) => unsafe {
+                let is_enabled: unsafe extern "C" fn() -> bool = core::mem::transmute(addr);
+                is_enabled()
+            },
+            None => false,
+        }
+    }
+
+    pub fn begin_section(name: &str) {
+        if let Some(addr) = BEGIN_SECTION.get() {
+            let begin_section: unsafe extern "C" fn(*const c_char) = unsafe { core::mem::transmute(addr) };
+            with_c_name(name, |name| unsafe { begin_section(name) });
+        }
+    }
+
+    pub fn end_section() {
+        if let Some(addr) = END_SECTION.get() {
+            unsafe {
+                let end_section: unsafe extern "C" fn() = core::mem::transmute(addr);
+                end_section();
+            }
+        }
+    }
+
+    pub fn set_counter(name: &str, value: i64) {
+        if let Some(addr) = SET_COUNTER.get() {
+            let set_counter: unsafe extern "C" fn(*const c_char, i64) = unsafe { core::mem::transmute(addr) };
+            with_c_name(name, |name| unsafe { set_counter(name, value) });
+        }
+    }
+}
+
+#[cfg(all(feature = "atrace", not(target_os = "android"), not(test)))]
+pub mod atrace {
+    #[inline(always)]
+    pub fn is_enabled() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    pub fn begin_section(_name: &str) {
+    }
+
+    #[inline(always)]
+    pub fn end_section() {
+    }
+
+    #[inline(always)]
+    pub fn set_counter(_name: &str, _value: i64) {
+    }
+}
+
+#[cfg(all(feature = "atrace", test))]
+pub mod atrace {
+    use super::capture::{ATRACE_DISABLED, TRACES};
+
+    #[inline(always)]
+    pub fn is_enabled() -> bool {
+        !ATRACE_DISABLED.with(|disabled| disabled.get())
+    }
+
+    pub fn begin_section(name: &str) {
+        TRACES.with(|traces| traces.borrow_mut().push(format!("begin {}", name)));
+    }
+
+    pub fn end_section() {
+        TRACES.with(|traces| traces.borrow_mut().push("end".into()));
+    }
+
+    pub fn set_counter(name: &str, value: i64) {
+        TRACES.with(|traces| traces.borrow_mut().push(format!("counter {}={}", name, value)));
+    }
+}
+
+#[cfg(test)]
+pub mod capture {
+    //! Captures messages written by current thread.
+
+    use std::cell::RefCell;
+
+    ///`(log_id, prio, name, data)` of persistent file.
+    pub type PmsgFile = (i32, i32, String, Vec<u8>);
+
+    std::thread_local! {
+        pub static LOGS: RefCell<Vec<(i32, String, String)>> = const { RefCell::new(Vec::new()) };
+        pub static STRICT: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+        pub static TRACES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        pub static ATRACE_DISABLED: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+        pub static LOG_IDS: RefCell<Vec<i32>> = const { RefCell::new(Vec::new()) };
+        pub static EVENTS: RefCell<Vec<(i32, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+        pub static SECURITY_EVENTS: RefCell<Vec<(i32, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+        pub static ABORT_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+        pub static DEFAULT_TAG: RefCell<Option<String>> = const { RefCell::new(None) };
+        pub static LOGGABLE_TAGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        pub static NOT_LOGGABLE: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+        pub static PMSG: RefCell<Vec<PmsgFile>> = const { RefCell::new(Vec::new()) };
+        #[cfg(feature = "source-location")]
+        pub static LOCATIONS: RefCell<Vec<(String, u32)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    ///Serializes test changing process-wide default tag with tests relying on it.
+    pub static DEFAULT_TAG_LOCK: std::sync::RwLock<()> = std::sync::RwLock::new(());
+
+    ///Keeps default tag from being changed by other tests, while guard is alive.
+    pub fn keep_default_tag() -> std::sync::RwLockReadGuard<'static, ()> {
+        match DEFAULT_TAG_LOCK.read() {
+            Ok(lock) => lock,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    static INDENT_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[cfg(feature = "std")]
+    ///Guard of process-wide indentation width, which is reset on drop.
+    pub struct Indent {
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Drop for Indent {
+        fn drop(&mut self) {
+            crate::set_indent(0);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    ///Sets indentation width, preventing other tests from changing it while guard is alive.
+    pub fn set_indent(width: usize) -> Indent {
+        let lock = match INDENT_LOCK.lock() {
+            Ok(lock) => lock,
+            Err(error) => error.into_inner(),
+        };
+        crate::set_indent(width);
+        Indent {
+            _lock: lock,
+        }
+    }
+
+    ///Returns `(prio, tag, text)` written so far, clearing captured messages.
+    pub fn take() -> Vec<(i32, String, String)> {
+        LOGS.with(|logs| core::mem::take(&mut *logs.borrow_mut()))
+    }
+
+    ///Returns log buffers of messages written so far via `__android_log_buf_write`, clearing them.
+    pub fn take_log_ids() -> Vec<i32> {
+        LOG_IDS.with(|ids| core::mem::take(&mut *ids.borrow_mut()))
+    }
+
+    ///Returns `(tag, payload)` of binary events written so far, clearing them.
+    pub fn take_events() -> Vec<(i32, Vec<u8>)> {
+        EVENTS.with(|events| core::mem::take(&mut *events.borrow_mut()))
+    }
+
+    ///Returns `(tag, payload)` of security events written so far, clearing them.
+    pub fn take_security_events() -> Vec<(i32, Vec<u8>)> {
+        SECURITY_EVENTS.with(|events| core::mem::take(&mut *events.borrow_mut()))
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{AsTag, LogPriority, Writer};
+
+use core::fmt::{self, Write};
+use std::string::String;
+use std::vec::Vec;
+
+const SEPARATOR: &str = "  ";
+
+///Table with aligned columns, written one row per entry.
+///
+///```rust,no_run
+///use androidy_log::{LogPriority, Table};
+///
+///let mut table = Table::new(["id", "state"]);
+///table.row(["1", "connected"]).row(["2", "idle"]);
+///table.log("Connections", LogPriority::INFO);
+/////id  state
+/////--  ---------
+/////1   connected
+/////2   idle
+///```
+pub struct Table {
+    rows: Vec<Vec<String>>,
+    widths: Vec<usize>,
+}
+
+impl Table {
+    ///Creates new table with columns `header`.
+    pub fn new<I: IntoIterator>(header: I) -> Self where I::Item: fmt::Display {
+        let mut table = Self {
+            rows: Vec::new(),
+            widths: Vec::new(),
+        };
+        table.row(header);
+        table
+    }
+
+    ///Adds row with `cells`.
+    ///
+    ///Rows may have different number of cells.
+    pub fn row<I: IntoIterator>(&mut self, cells: I) -> &mut Self where I::Item: fmt::Display {
+        let mut row = Vec::new();
+        for (idx, cell) in cells.into_iter().enumerate() {
+            let mut text = String::new();
+            let _ = write!(text, "{}", cell);
+
+            let width = text.chars().count();
+            match self.widths.get_mut(idx) {
+                Some(max) => *max = (*max).max(width),
+                None => self.widths.push(width),
+            }
+            row.push(text);
+        }
+        self.rows.push(row);
+        self
+    }
+
+    ///Returns number of rows, excluding header.
+    pub fn len(&self) -> usize {
+        self.rows.len() - 1
+    }
+
+    ///Returns whether there are no rows, excluding header.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Writes table using `writer`, as separate entry per row.
+    ///
+    ///Header is followed by line of dashes, while any data already buffered is flushed beforehand.
+    pub fn write_to<T: AsTag>(&self, writer: &mut Writer<T>) {
+        writer.flush();
+
+        let mut rows = self.rows.iter();
+        if let Some(header) = rows.next() {
+            self.write_row(writer, header.iter().map(String::as_str));
+            let dashes: Vec<String> = header.iter().map(|cell| "-".repeat(cell.chars().count())).collect();
+            self.write_row(writer, dashes.iter().map(String::as_str));
+        }
+        for row in rows {
+            self.write_row(writer, row.iter().map(String::as_str));
+        }
+    }
+
+    #[inline]
+    ///Writes table with `tag` and `prio`, as separate entry per row.
+    pub fn log(&self, tag: &str, prio: LogPriority) {
+        self.write_to(&mut Writer::new(tag, prio));
+    }
+
+    fn write_row<'a, T: AsTag, I: Iterator<Item = &'a str>>(&self, writer: &mut Writer<T>, cells: I) {
+        let mut padding = 0;
+        for (cell, width) in cells.zip(self.widths.iter()) {
+            for _ in 0..padding {
+                writer.write_data(b" ");
+            }
+            writer.write_data(cell.as_bytes());
+            padding = width - cell.chars().count() + SEPARATOR.len();
+        }
+        //Empty rows still produce entry
+        if writer.buffer().is_empty() {
+            writer.write_data(b" ");
+        }
+        writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_log_aligned_table() {
+        let mut table = Table::new(["id", "state"]);
+        assert!(table.is_empty());
+        table.row(["1", "connected"]).row(["1000", "ロリ"]).row(["2"]);
+        assert_eq!(table.len(), 3);
+        table.log("Table", LogPriority::INFO);
+
+        let logs = capture::take();
+        let rows: Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(rows, ["id    state", "--    -----", "1     connected", "1000  ロリ", "2"]);
+    }
+}
@@ -0,0 +1,141 @@
+use core::fmt;
+
+///Message template with named fields, rendered only when it is written.
+///
+///Placeholders are specified as `{name}`, while `{{` and `}}` are used to escape braces.
+///Placeholders without corresponding field are written as it is.
+///
+///Template and fields are accessible separately, allowing to export them as structured data.
+///
+///```rust
+///use androidy_log::Template;
+///
+///let uid = 42;
+///let fields: &[(&'static str, &dyn core::fmt::Display)] = &[("id", &uid)];
+///let template = Template::new("user {id} logged in", fields);
+///assert_eq!(template.to_string(), "user 42 logged in");
+///```
+#[derive(Clone, Copy)]
+pub struct Template<'a> {
+    template: &'static str,
+    fields: &'a [(&'static str, &'a dyn fmt::Display)],
+}
+
+impl<'a> Template<'a> {
+    #[inline(always)]
+    ///Creates new template.
+    pub const fn new(template: &'static str, fields: &'a [(&'static str, &'a dyn fmt::Display)]) -> Self {
+        Self {
+            template,
+            fields,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns template text.
+    pub const fn template(&self) -> &'static str {
+        self.template
+    }
+
+    #[inline(always)]
+    ///Returns template fields.
+    pub const fn fields(&self) -> &'a [(&'static str, &'a dyn fmt::Display)] {
+        self.fields
+    }
+
+    fn field(&self, name: &str) -> Option<&'a dyn fmt::Display> {
+        self.fields.iter().find(|(field, _)| *field == name).map(|(_, value)| *value)
+    }
+}
+
+impl fmt::Display for Template<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = self.template;
+
+        while let Some(idx) = text.find(['{', '}']) {
+            fmt.write_str(&text[..idx])?;
+            let rest = &text[idx..];
+
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                fmt.write_str(&rest[..1])?;
+                text = &rest[2..];
+            } else if let (true, Some(end)) = (rest.starts_with('{'), rest.find('}')) {
+                match self.field(&rest[1..end]) {
+                    Some(value) => fmt::Display::fmt(value, fmt)?,
+                    None => fmt.write_str(&rest[..=end])?,
+                }
+                text = &rest[end + 1..];
+            } else {
+                fmt.write_str(&rest[..1])?;
+                text = &rest[1..];
+            }
+        }
+
+        fmt.write_str(text)
+    }
+}
+
+impl fmt::Debug for Template<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = fmt.debug_map();
+        for (name, value) in self.fields {
+            fields.entry(name, &format_args!("{}", value));
+        }
+        fields.finish()?;
+        fmt.write_str(" ")?;
+        fmt::Debug::fmt(self.template, fmt)
+    }
+}
+
+#[macro_export]
+///Writes message template with named fields, that are rendered only if priority is enabled.
+///
+///Priority can be either name of `LogPriority` variant or expression.
+///
+///```rust,no_run
+///let uid = 42;
+///androidy_log::log_tpl!(INFO, "Auth", "user {id} logged in", id = uid);
+///```
+macro_rules! log_tpl {
+    ($prio:expr, $tag:expr, $template:literal $(, $name:ident = $value:expr)* $(,)?) => {{
+        let prio = $crate::__priority!($prio);
+        let tag: &str = $tag;
+        if $crate::is_loggable(prio, tag) {
+            let fields: &[(&'static str, &dyn core::fmt::Display)] = &[$((stringify!($name), &$value)),*];
+            let template = $crate::Template::new($template, fields);
+            let mut writer = $crate::Writer::new(tag, prio);
+            let _ = core::fmt::Write::write_fmt(&mut writer, format_args!("{}", template));
+            drop(writer);
+        }
+    }}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    #[test]
+    fn should_render_template() {
+        let id = 42;
+        let name = "guest";
+        let fields: &[(&'static str, &dyn core::fmt::Display)] = &[("id", &id), ("name", &name)];
+        let template = Template::new("user {id} ({name}) {{logged}} in {unknown} }{", fields);
+        assert_eq!(template.to_string(), "user 42 (guest) {logged} in {unknown} }{");
+        assert_eq!(template.fields().len(), 2);
+        assert_eq!(template.fields()[0].0, "id");
+        assert_eq!(format!("{:?}", template), "{\"id\": 42, \"name\": guest} \"user {id} ({name}) {{logged}} in {unknown} }{\"");
+    }
+
+    #[test]
+    fn should_log_template() {
+        let uid = 7;
+        let prio = LogPriority::WARN;
+        crate::log_tpl!(INFO, "Auth", "user {id} logged in", id = uid);
+        crate::log_tpl!(prio, "Auth", "no fields");
+
+        let logs = capture::take();
+        assert_eq!(logs, [(LogPriority::INFO as i32, "Auth".to_owned(), "user 7 logged in".to_owned()), (LogPriority::WARN as i32, "Auth".to_owned(), "no fields".to_owned())]);
+    }
+}
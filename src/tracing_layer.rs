@@ -0,0 +1,479 @@
+use crate::{AsTag, LogPriority, TagHandle, Writer};
+#[cfg(feature = "atrace")]
+use crate::sys;
+
+use core::fmt::{self, Write};
+use std::string::String;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+#[inline]
+fn level_priority(level: &Level) -> LogPriority {
+    match *level {
+        Level::ERROR => LogPriority::ERROR,
+        Level::WARN => LogPriority::WARN,
+        Level::INFO => LogPriority::INFO,
+        Level::DEBUG => LogPriority::DEBUG,
+        Level::TRACE => LogPriority::VERBOSE,
+    }
+}
+
+//Writes either `message` field or other fields as `name=value`, separated by space.
+struct Fields<'a, W: Write> {
+    writer: &'a mut W,
+    message: bool,
+    is_empty: bool,
+    //Fields to write, all if `None`.
+    filter: Option<&'a [&'static str]>,
+    //Whether to skip `log.*` fields of records converted by `tracing-log`.
+    skip_log: bool,
+}
+
+impl<'a, W: Write> Fields<'a, W> {
+    #[inline]
+    fn new(writer: &'a mut W, message: bool, is_empty: bool) -> Self {
+        Self {
+            writer,
+            message,
+            is_empty,
+            filter: None,
+            skip_log: false,
+        }
+    }
+}
+
+impl<W: Write> Visit for Fields<'_, W> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{}", value))
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.message != (field.name() == "message") {
+            return;
+        }
+        if self.skip_log && field.name().starts_with("log.") {
+            return;
+        }
+        if let Some(filter) = self.filter {
+            if !filter.contains(&field.name()) {
+                return;
+            }
+        }
+
+        if !self.is_empty {
+            let _ = self.writer.write_str(" ");
+        }
+        self.is_empty = false;
+        let _ = if self.message {
+            write!(self.writer, "{:?}", value)
+        } else {
+            write!(self.writer, "{}={:?}", field.name(), value)
+        };
+    }
+}
+
+#[cfg(feature = "atrace")]
+//Sets ATrace counter for every integer field, named as `counter.<name>`.
+struct Counters;
+
+#[cfg(feature = "atrace")]
+impl Visit for Counters {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if let Some(name) = field.name().strip_prefix("counter.") {
+            sys::atrace::set_counter(name, value);
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record_i64(field, value.min(i64::MAX as u64) as i64)
+    }
+
+    #[inline(always)]
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///Context of enclosing spans, written as prefix of event, like `[request{id=42}:body] message`.
+pub enum SpanContext {
+    ///Spans are not written.
+    Hidden,
+    ///Only names of spans are written.
+    Names,
+    ///Names of spans are written with specified fields.
+    Fields(&'static [&'static str]),
+    ///Names of spans are written with all fields.
+    All,
+}
+
+impl SpanContext {
+    //Returns filter of span's fields, unless fields are not written.
+    fn filter(self) -> Option<Option<&'static [&'static str]>> {
+        match self {
+            SpanContext::Hidden | SpanContext::Names => None,
+            SpanContext::Fields(filter) => Some(Some(filter)),
+            SpanContext::All => Some(None),
+        }
+    }
+}
+
+//Span's fields, formatted as `name=value` pairs, stored in span's extensions.
+struct SpanFields(String);
+
+#[cfg(feature = "atrace")]
+//Number of ATrace sections begun by entering span, stored in span's extensions.
+struct Sections(usize);
+
+fn write_spans<S: Subscriber + for<'a> LookupSpan<'a>, T: AsTag>(writer: &mut Writer<T>, event: &Event<'_>, ctx: &Context<'_, S>) {
+    let scope = match ctx.event_scope(event) {
+        Some(scope) => scope,
+        None => return,
+    };
+
+    let mut is_empty = true;
+    for span in scope.from_root() {
+        let _ = writer.write_str(if is_empty { "[" } else { ":" });
+        is_empty = false;
+        let _ = writer.write_str(span.name());
+        if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+            if !fields.is_empty() {
+                let _ = write!(writer, "{{{}}}", fields);
+            }
+        }
+    }
+    if !is_empty {
+        let _ = writer.write_str("] ");
+    }
+}
+
+//Fields of `log` record, converted by `tracing-log` into event with `log` target.
+#[derive(Default)]
+struct LogRecord {
+    target: Option<String>,
+    #[cfg(feature = "source-location")]
+    file: Option<String>,
+    #[cfg(feature = "source-location")]
+    line: Option<u32>,
+}
+
+impl LogRecord {
+    fn new(event: &Event<'_>) -> Self {
+        let mut record = Self::default();
+        if event.metadata().target() == "log" {
+            event.record(&mut record);
+        }
+        record
+    }
+
+    //Returns source location of event, with `log` record's one having priority.
+    fn location<'a>(&'a self, _event: &Event<'_>) -> Option<(&'a str, u32)> {
+        #[cfg(feature = "source-location")]
+        if self.target.is_some() {
+            return self.file.as_deref().zip(self.line);
+        }
+        #[cfg(feature = "source-location")]
+        return _event.metadata().file().zip(_event.metadata().line());
+        #[cfg(not(feature = "source-location"))]
+        None
+    }
+}
+
+impl Visit for LogRecord {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "log.target" => self.target = Some(value.into()),
+            #[cfg(feature = "source-location")]
+            "log.file" => self.file = Some(value.into()),
+            _ => (),
+        }
+    }
+
+    #[cfg(feature = "source-location")]
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "log.line" {
+            self.line = core::convert::TryFrom::try_from(value).ok();
+        }
+    }
+
+    #[inline(always)]
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {
+    }
+}
+
+fn write_event<S: Subscriber + for<'a> LookupSpan<'a>, T: AsTag>(mut writer: Writer<T>, target: Option<&str>, from_log: bool, _location: Option<(&str, u32)>, spans: SpanContext, event: &Event<'_>, ctx: &Context<'_, S>) {
+    #[cfg(feature = "source-location")]
+    if let Some((file, line)) = _location {
+        //Writer is dropped before returning, hence file outlives it
+        writer.set_location(unsafe { core::mem::transmute::<&str, &'static str>(file) }, line);
+    }
+    if spans != SpanContext::Hidden {
+        write_spans(&mut writer, event, ctx);
+    }
+    if let Some(target) = target {
+        let _ = write!(writer, "{}: ", target);
+    }
+
+    let mut fields = Fields::new(&mut writer, true, true);
+    fields.skip_log = from_log;
+    event.record(&mut fields);
+    fields.message = false;
+    event.record(&mut fields);
+}
+
+///`tracing-subscriber` layer, that writes events via `Writer`.
+///
+///Event is written as its `message`, followed by other fields as ` name=value` pairs.
+///Records of `log` crate, converted by `tracing-log`, are written with their original target.
+///With `source-location` feature, file and line of event or original record are set via `Writer::set_location`.
+///By default it is prefixed by enclosing spans with all their fields, see `SpanContext`.
+///
+///With `atrace` feature, entered spans are also written as ATrace sections, while integer fields
+///named as `counter.<name>` set ATrace counters, when tracing is enabled by system.
+///
+///```rust,no_run
+///use tracing_subscriber::layer::SubscriberExt;
+///
+///let subscriber = tracing_subscriber::registry().with(androidy_log::LogcatLayer::new());
+///tracing::subscriber::set_global_default(subscriber).expect("to set subscriber");
+///```
+pub struct LogcatLayer {
+    tag: Option<TagHandle>,
+    spans: SpanContext,
+}
+
+impl LogcatLayer {
+    #[inline(always)]
+    ///Creates layer, that uses event's target as tag, truncated to first 23 bytes.
+    pub const fn new() -> Self {
+        Self {
+            tag: None,
+            spans: SpanContext::All,
+        }
+    }
+
+    #[inline(always)]
+    ///Creates layer, that uses `tag` for all events, truncated to first 23 bytes.
+    ///
+    ///Event's target is written as prefix of message instead.
+    pub const fn with_tag(tag: &str) -> Self {
+        Self {
+            tag: Some(TagHandle::new(tag)),
+            spans: SpanContext::All,
+        }
+    }
+
+    #[inline(always)]
+    ///Sets context of enclosing spans to write.
+    pub const fn with_span_context(mut self, spans: SpanContext) -> Self {
+        self.spans = spans;
+        self
+    }
+}
+
+impl Default for LogcatLayer {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> tracing_subscriber::Layer<S> for LogcatLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let filter = match self.spans.filter() {
+            Some(filter) => filter,
+            None => return,
+        };
+
+        if let Some(span) = ctx.span(id) {
+            let mut text = String::new();
+            let mut fields = Fields::new(&mut text, false, true);
+            fields.filter = filter;
+            attrs.record(&mut fields);
+            span.extensions_mut().insert(SpanFields(text));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let filter = match self.spans.filter() {
+            Some(filter) => filter,
+            None => return,
+        };
+
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if let Some(SpanFields(text)) = extensions.get_mut::<SpanFields>() {
+                let is_empty = text.is_empty();
+                let mut fields = Fields::new(text, false, is_empty);
+                fields.filter = filter;
+                values.record(&mut fields);
+            }
+        }
+    }
+
+    #[cfg(feature = "atrace")]
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if sys::atrace::is_enabled() {
+            if let Some(span) = ctx.span(id) {
+                sys::atrace::begin_section(span.name());
+                let mut extensions = span.extensions_mut();
+                match extensions.get_mut::<Sections>() {
+                    Some(Sections(count)) => *count += 1,
+                    None => extensions.insert(Sections(1)),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "atrace")]
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(Sections(count)) = span.extensions_mut().get_mut::<Sections>() {
+                if *count > 0 {
+                    *count -= 1;
+                    sys::atrace::end_section();
+                }
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        #[cfg(feature = "atrace")]
+        if sys::atrace::is_enabled() {
+            event.record(&mut Counters);
+        }
+
+        let metadata = event.metadata();
+        let log_record = LogRecord::new(event);
+        let target = log_record.target.as_deref().unwrap_or(metadata.target());
+        let from_log = log_record.target.is_some();
+        let location = log_record.location(event);
+        let prio = level_priority(metadata.level());
+        match &self.tag {
+            Some(tag) => if crate::is_loggable(prio, tag.as_str()) {
+                write_event(tag.writer(prio), Some(target), from_log, location, self.spans, event, &ctx);
+            },
+            None => if crate::is_loggable(prio, target) {
+                write_event(Writer::new(target, prio), None, from_log, location, self.spans, event, &ctx);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogcatLayer, SpanContext};
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn should_write_events() {
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "Net", peer = "localhost", port = 8080, "connected {}", 1);
+            tracing::trace!(target: "Net", done = true);
+        });
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::with_tag("Tracing"));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(target: "Audio", "failed");
+        });
+
+        let logs = capture::take();
+        assert_eq!(logs, [
+            (LogPriority::INFO as i32, "Net".into(), "connected 1 peer=localhost port=8080".into()),
+            (LogPriority::VERBOSE as i32, "Net".into(), "done=true".into()),
+            (LogPriority::ERROR as i32, "Tracing".into(), "Audio: failed".into()),
+        ]);
+    }
+
+    #[test]
+    fn should_write_span_context() {
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new());
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("request", id = 42, path = tracing::field::Empty);
+            let _request = request.enter();
+            request.record("path", "/index");
+            let _body = tracing::info_span!("body").entered();
+            tracing::info!(target: "Net", "handling body");
+        });
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new().with_span_context(SpanContext::Fields(&["id"])));
+        tracing::subscriber::with_default(subscriber, || {
+            let _request = tracing::info_span!("request", id = 42, user = "admin").entered();
+            tracing::info!(target: "Net", "filtered");
+        });
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new().with_span_context(SpanContext::Hidden));
+        tracing::subscriber::with_default(subscriber, || {
+            let _request = tracing::info_span!("request", id = 42).entered();
+            tracing::info!(target: "Net", "hidden");
+        });
+
+        let logs = capture::take();
+        let texts: std::vec::Vec<_> = logs.iter().map(|(_, _, text)| text.as_str()).collect();
+        assert_eq!(texts, ["[request{id=42 path=/index}:body] handling body", "[request{id=42}] filtered", "hidden"]);
+    }
+
+    #[cfg(feature = "atrace")]
+    #[test]
+    fn should_write_atrace_sections() {
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new().with_span_context(SpanContext::Hidden));
+        tracing::subscriber::with_default(subscriber, || {
+            let _frame = tracing::info_span!("frame").entered();
+            {
+                let _draw = tracing::info_span!("draw").entered();
+                tracing::event!(target: "Render", tracing::Level::DEBUG, counter.quads = 12u64, counter.skipped = -1i64, name = 5, "drawn");
+            }
+        });
+
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new().with_span_context(SpanContext::Hidden));
+        tracing::subscriber::with_default(subscriber, || {
+            crate::sys::capture::ATRACE_DISABLED.with(|disabled| disabled.set(true));
+            let span = tracing::info_span!("disabled").entered();
+            crate::sys::capture::ATRACE_DISABLED.with(|disabled| disabled.set(false));
+            drop(span);
+        });
+
+        let traces = crate::sys::capture::TRACES.with(|traces| core::mem::take(&mut *traces.borrow_mut()));
+        assert_eq!(traces, ["begin frame", "begin draw", "counter quads=12", "counter skipped=-1", "end", "end"]);
+        assert_eq!(capture::take()[0].2, "drawn counter.quads=12 counter.skipped=-1 name=5");
+    }
+
+    #[test]
+    fn should_normalize_log_records() {
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::event!(target: "log", tracing::Level::WARN, log.target = "Audio", log.module_path = "app::audio", log.file = "src/audio.rs", log.line = 10u32, "underrun");
+            tracing::event!(target: "log", tracing::Level::INFO, log.line = 1u32, "native");
+        });
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::with_tag("Tracing"));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::event!(target: "log", tracing::Level::WARN, log.target = "Audio", "underrun");
+        });
+
+        let logs = capture::take();
+        assert_eq!(logs, [
+            (LogPriority::WARN as i32, "Audio".into(), "underrun".into()),
+            (LogPriority::INFO as i32, "log".into(), "native log.line=1".into()),
+            (LogPriority::WARN as i32, "Tracing".into(), "Audio: underrun".into()),
+        ]);
+    }
+
+    #[cfg(feature = "source-location")]
+    #[test]
+    fn should_set_source_location() {
+        let subscriber = tracing_subscriber::registry().with(LogcatLayer::new());
+        let line = tracing::subscriber::with_default(subscriber, || {
+            tracing::event!(target: "log", tracing::Level::WARN, log.target = "Audio", log.file = "src/audio.rs", log.line = 10u32, "underrun");
+            tracing::event!(target: "log", tracing::Level::WARN, log.target = "Audio", "unknown");
+            tracing::info!(target: "Net", "native");
+            line!() - 1
+        });
+
+        let locations = capture::LOCATIONS.with(|locations| core::mem::take(&mut *locations.borrow_mut()));
+        assert_eq!(locations, [("src/audio.rs".into(), 10), (file!().into(), line)]);
+        assert_eq!(capture::take().len(), 3);
+    }
+}
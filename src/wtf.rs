@@ -0,0 +1,90 @@
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+///Handler of "What a Terrible Failure" messages, receiving message text.
+pub type WtfHandler = fn(&str);
+
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+#[inline]
+///Sets handler, that is called with every message written via `wtf!` or `Writer::wtf`.
+///
+///Handler is called before message is written, and it is not set by default.
+pub fn set_wtf_handler(handler: Option<WtfHandler>) {
+    let handler = match handler {
+        Some(handler) => handler as *mut (),
+        None => ptr::null_mut(),
+    };
+    HANDLER.store(handler, Ordering::Release);
+}
+
+#[inline]
+pub(crate) fn handler() -> Option<WtfHandler> {
+    let handler = HANDLER.load(Ordering::Acquire);
+    if handler.is_null() {
+        None
+    } else {
+        //Only function pointers are stored
+        Some(unsafe { core::mem::transmute::<*mut (), WtfHandler>(handler) })
+    }
+}
+
+#[macro_export]
+///Writes "What a Terrible Failure" message with FATAL priority, like `android.util.Log.wtf`.
+///
+///Calls handler set via `set_wtf_handler`, if any.
+///Tag can be specified as `tag: "MyTag"`, otherwise default tag is used.
+///
+///```rust,no_run
+///androidy_log::wtf!("Impossible state {}", 1);
+///androidy_log::wtf!(tag: "Audio", "Buffer underrun");
+///```
+macro_rules! wtf {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::Writer::new($tag, $crate::LogPriority::FATAL).wtf(format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::Writer::new_default($crate::LogPriority::FATAL).wtf(format_args!($($arg)+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LogPriority;
+    use crate::sys::capture;
+
+    use core::cell::RefCell;
+    use std::string::String;
+
+    std::thread_local! {
+        static HANDLED: RefCell<String> = const { RefCell::new(String::new()) };
+    }
+
+    #[test]
+    fn should_write_wtf() {
+        let _tag = crate::sys::capture::keep_default_tag();
+        super::set_wtf_handler(Some(|text| HANDLED.with(|handled| handled.borrow_mut().push_str(text))));
+        #[cfg(feature = "std")]
+        {
+            let _scope = crate::CorrelationScope::enter(1);
+            crate::wtf!("long {}", "a".repeat(5000));
+            assert_eq!(HANDLED.with(|handled| handled.take()), std::format!("long {}", "a".repeat(5000)));
+            let logs = capture::take();
+            assert_eq!(logs.len(), 2);
+            assert!(logs[0].2.starts_with("[c:1] long aaa"));
+        }
+        crate::wtf!("state {}", 1);
+        let mut writer = crate::Writer::new("Writer", LogPriority::INFO);
+        writer.write_data(b"pending");
+        writer.wtf(format_args!("failure"));
+        writer.write_data(b"info");
+        drop(writer);
+        super::set_wtf_handler(None);
+        crate::wtf!(tag: "Unhandled", "2");
+
+        assert_eq!(HANDLED.with(|handled| handled.take()), "state 1failure");
+        let logs = capture::take();
+        let logs: std::vec::Vec<_> = logs.iter().map(|(prio, tag, text)| (*prio, tag.as_str(), text.as_str())).collect();
+        assert_eq!(logs, [(7, "Rust", "state 1"), (4, "Writer", "pending"), (7, "Writer", "failure"), (4, "Writer", "info"), (7, "Unhandled", "2")]);
+    }
+}